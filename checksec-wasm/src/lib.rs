@@ -2,11 +2,18 @@
 
 use checksec::{
     binary::Binary,
-    checksec,
+    checksec_binaries,
     compression::{compress, decompress},
-    sarif, VERSION,
+    diff::Diff,
+    error::{Error, ErrorInfo},
+    policy::Policy,
+    sarif,
+    severity::SeverityProfile,
+    VERSION,
 };
+use serde::Serialize as _;
 use serde_derive::{Deserialize, Serialize};
+use std::path::Path;
 use wasm_bindgen::prelude::*;
 
 // Hold checksec results along with other web-related metadata.
@@ -26,22 +33,26 @@ pub struct CheckSecJs {
 /// # Returns
 ///
 /// Returns a `Result` containing:
-/// - `Ok(JsValue)` wrapping a `CheckSecJs` struct with:
+/// - `Ok(JsValue)` wrapping an array of `CheckSecJs` structs, one per binary
+///   of the file (e.g. per member of a static library), each with:
 ///   - version info
 ///   - checksec analysis data
 /// - `Err(JsValue)` wrapping error information if the analysis fails.
 ///
 /// # Errors
 ///
-/// Returns an error if the underlying `checksec` function fails to process the file.
-/// The error is serialized to a `JsValue` suitable for consumption in the WebAssembly context.
+/// Returns an error if the results can not be serialized. Files which can not
+/// be parsed are reported as a binary with an error blob.
 #[wasm_bindgen]
 pub fn checksec_web(buffer: &[u8], filename: String) -> Result<JsValue, JsValue> {
-    let report = CheckSecJs {
-        version: VERSION.into(),
-        report: checksec(buffer, filename),
-    };
-    Ok(serde_wasm_bindgen::to_value(&report)?)
+    let reports: Vec<CheckSecJs> = checksec_binaries(buffer, Path::new(&filename))
+        .into_iter()
+        .map(|report| CheckSecJs {
+            version: VERSION.into(),
+            report,
+        })
+        .collect();
+    Ok(serde_wasm_bindgen::to_value(&reports)?)
 }
 
 /// Compresses and encodes a serialized `CheckSecJs` structure.
@@ -67,7 +78,7 @@ pub fn checksec_compress(js_representation: JsValue) -> Result<JsValue, JsValue>
     let parsed: CheckSecJs = serde_wasm_bindgen::from_value(js_representation)
         .map_err(|_| JsValue::from_str("Error converting JS value to Rust struct"))?;
 
-    let encoded_str = compress(&parsed).map_err(|err_msg| JsValue::from_str(&err_msg))?;
+    let encoded_str = compress(&parsed).map_err(|err| JsValue::from_str(&err.to_string()))?;
 
     serde_wasm_bindgen::to_value(&encoded_str)
         .map_err(|e| JsValue::from_str(&format!("Serialization error: {e}")))
@@ -83,7 +94,7 @@ pub fn checksec_compress(js_representation: JsValue) -> Result<JsValue, JsValue>
 ///
 /// Returns a `Result` containing:
 /// - `Ok(JsValue)` with the decoded and decompressed `CheckSecJs` structure.
-/// - `Err(JsValue)` containing an error message describing the failure.
+/// - `Err(JsValue)` containing the error with its `kind` and `message`.
 ///
 /// # Errors
 ///
@@ -92,10 +103,10 @@ pub fn checksec_compress(js_representation: JsValue) -> Result<JsValue, JsValue>
 /// - Serialization of the decompressed `CheckSecJs` struct into a `JsValue` fails.
 #[wasm_bindgen]
 pub fn checksec_decompress(buffer: &[u8]) -> Result<JsValue, JsValue> {
-    let decompressed: Result<CheckSecJs, String> = decompress(buffer);
+    let decompressed: Result<CheckSecJs, Error> = decompress(buffer);
     match decompressed {
         Ok(value) => Ok(serde_wasm_bindgen::to_value(&value)?),
-        Err(err) => Err(serde_wasm_bindgen::to_value(&err)?),
+        Err(err) => Err(serde_wasm_bindgen::to_value(&ErrorInfo::from(err))?),
     }
 }
 
@@ -104,6 +115,8 @@ pub fn checksec_decompress(buffer: &[u8]) -> Result<JsValue, JsValue> {
 /// # Arguments
 ///
 /// * `js_representation` - A `JsValue` representing a serialized `Vec<Binary>` structure from JavaScript.
+/// * `profile` - Optional severity profile of failed checks, either a built-in name
+///   (`default`, `strict`, `informational`) or a TOML/JSON mapping.
 ///
 /// # Returns
 ///
@@ -115,14 +128,84 @@ pub fn checksec_decompress(buffer: &[u8]) -> Result<JsValue, JsValue> {
 ///
 /// This function returns an error if:
 /// - The input `JsValue` cannot be deserialized into a `CheckSecJs` struct.
+/// - The severity profile is malformed.
 /// - The SARIF report generation fails.
 /// - Serialization of the SARIF report string into a `JsValue` fails.
 #[wasm_bindgen]
-pub fn generate_sarif_report(js_representation: JsValue) -> Result<JsValue, JsValue> {
+pub fn generate_sarif_report(
+    js_representation: JsValue,
+    profile: Option<String>,
+) -> Result<JsValue, JsValue> {
     let reports: Vec<Binary> = serde_wasm_bindgen::from_value(js_representation)
         .map_err(|_| JsValue::from_str("Error converting JS value to Rust struct"))?;
-    match sarif::get_sarif_report(&reports) {
+    let profile = match profile.as_deref() {
+        None => SeverityProfile::default(),
+        Some(profile) => match SeverityProfile::builtin(profile) {
+            Some(profile) => profile,
+            None => SeverityProfile::parse(profile)
+                .map_err(|err| JsValue::from_str(&err.to_string()))?,
+        },
+    };
+    match sarif::get_sarif_report_with_profile(&reports, &profile) {
         Ok(report_string) => Ok(serde_wasm_bindgen::to_value(&report_string)?),
         Err(err) => Err(serde_wasm_bindgen::to_value(&err.to_string())?),
     }
 }
+
+/// Checks checksec reports against a policy of required mitigations.
+///
+/// # Arguments
+///
+/// * `js_representation` - A `JsValue` representing a serialized `Vec<Binary>` structure from JavaScript.
+/// * `policy` - The policy in TOML or JSON format.
+///
+/// # Returns
+///
+/// Returns a `Result` containing:
+/// - `Ok(JsValue)` with the list of policy violations, empty if the reports comply.
+/// - `Err(JsValue)` with an error message describing the failure.
+///
+/// # Errors
+///
+/// This function returns an error if:
+/// - The input `JsValue` cannot be deserialized into a `Vec<Binary>`.
+/// - The policy is malformed.
+/// - Serialization of the violations into a `JsValue` fails.
+#[wasm_bindgen]
+pub fn check_policy(js_representation: JsValue, policy: &str) -> Result<JsValue, JsValue> {
+    let reports: Vec<Binary> = serde_wasm_bindgen::from_value(js_representation)
+        .map_err(|_| JsValue::from_str("Error converting JS value to Rust struct"))?;
+    let policy = Policy::parse(policy).map_err(|err| JsValue::from_str(&err.to_string()))?;
+    Ok(policy
+        .check(&reports)
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
+
+/// Compares the checksec reports of two builds.
+///
+/// # Arguments
+///
+/// * `baseline` - A `JsValue` representing the serialized `Vec<Binary>` of the previous build.
+/// * `current` - A `JsValue` representing the serialized `Vec<Binary>` of the current build.
+///
+/// # Returns
+///
+/// Returns a `Result` containing:
+/// - `Ok(JsValue)` with the new, removed and changed binaries; changes which lost a
+///   mitigation have an `after_level` above their `before_level`.
+/// - `Err(JsValue)` with an error message describing the failure.
+///
+/// # Errors
+///
+/// This function returns an error if:
+/// - Either input `JsValue` cannot be deserialized into a `Vec<Binary>`.
+/// - Serialization of the differences into a `JsValue` fails.
+#[wasm_bindgen]
+pub fn compare_reports(baseline: JsValue, current: JsValue) -> Result<JsValue, JsValue> {
+    let baseline: Vec<Binary> = serde_wasm_bindgen::from_value(baseline)
+        .map_err(|_| JsValue::from_str("Error converting JS value to Rust struct"))?;
+    let current: Vec<Binary> = serde_wasm_bindgen::from_value(current)
+        .map_err(|_| JsValue::from_str("Error converting JS value to Rust struct"))?;
+    Ok(Diff::new(&baseline, &current)
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())?)
+}
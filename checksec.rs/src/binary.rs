@@ -1,11 +1,17 @@
 #[cfg(feature = "color")]
 use colored::Colorize;
+#[cfg(feature = "macho")]
+use goblin::mach::{
+    constants::cputype::get_arch_name_from_types, fat::FatArch,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use crate::compression::get_sha256_hash;
 #[cfg(feature = "elf")]
 use crate::elf;
+use crate::error::{Error, ErrorInfo};
 #[cfg(feature = "macho")]
 use crate::macho;
 #[cfg(feature = "pe")]
@@ -76,7 +82,7 @@ pub enum BinSpecificProperties {
     PE(pe::CheckSecResults),
     #[cfg(feature = "macho")]
     MachO(macho::CheckSecResults),
-    Error(String),
+    Error(ErrorInfo),
 }
 impl fmt::Display for BinSpecificProperties {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -92,10 +98,49 @@ impl fmt::Display for BinSpecificProperties {
     }
 }
 
+/// Architecture slice of a fat Mach-O binary
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct FatSlice {
+    /// Name of the architecture, e.g. `arm64`
+    pub architecture: String,
+    pub cputype: u32,
+    pub cpusubtype: u32,
+    /// Offset of the slice in the file
+    pub offset: u32,
+    pub size: u32,
+    /// Alignment of the slice as a power of two
+    pub align: u32,
+}
+
+#[cfg(feature = "macho")]
+impl From<&FatArch> for FatSlice {
+    fn from(fatarch: &FatArch) -> Self {
+        Self {
+            architecture: get_arch_name_from_types(
+                fatarch.cputype(),
+                fatarch.cpusubtype(),
+            )
+            .unwrap_or("Unknown")
+            .to_string(),
+            cputype: fatarch.cputype(),
+            cpusubtype: fatarch.cpusubtype(),
+            offset: fatarch.offset,
+            size: fatarch.size,
+            align: fatarch.align,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Blob {
     pub binarytype: BinType,
     pub properties: BinSpecificProperties,
+    /// ELF build ID, PE PDB GUID or Mach-O UUID
+    #[serde(default)]
+    pub build_id: Option<String>,
+    /// Slice of a fat Mach-O binary containing the blob
+    #[serde(default)]
+    pub slice: Option<FatSlice>,
 }
 
 impl Blob {
@@ -104,13 +149,40 @@ impl Blob {
         binarytype: BinType,
         properties: BinSpecificProperties,
     ) -> Self {
-        Self { binarytype, properties }
+        Self { binarytype, properties, build_id: None, slice: None }
+    }
+
+    #[must_use]
+    pub fn with_build_id(mut self, build_id: Option<String>) -> Self {
+        self.build_id = build_id;
+        self
+    }
+
+    #[must_use]
+    pub fn with_slice(mut self, slice: FatSlice) -> Self {
+        self.slice = Some(slice);
+        self
+    }
+
+    /// Blob of a binary which failed to parse
+    #[must_use]
+    pub fn error(err: &Error) -> Self {
+        Self::new(BinType::Error, BinSpecificProperties::Error(err.into()))
     }
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct Binary {
     pub file: PathBuf,
+    /// Hex encoded SHA-256 hash of the file contents
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// File size in bytes
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Modification time in seconds since the Unix epoch
+    #[serde(default)]
+    pub mtime: Option<u64>,
     pub blobs: Vec<Blob>,
     pub libraries: Vec<Binary>,
 }
@@ -118,6 +190,44 @@ pub struct Binary {
 impl Binary {
     #[must_use]
     pub fn new(file: PathBuf, blobs: Vec<Blob>) -> Self {
-        Self { file, blobs, libraries: vec![] }
+        Self {
+            file,
+            sha256: None,
+            size: None,
+            mtime: None,
+            blobs,
+            libraries: vec![],
+        }
+    }
+
+    /// Record the hash and size of the file contents.
+    #[must_use]
+    pub fn with_contents(mut self, bytes: &[u8]) -> Self {
+        self.sha256 = Some(hex::encode(get_sha256_hash(bytes)));
+        self.size = Some(bytes.len() as u64);
+        self
+    }
+
+    /// Check the runtime search path directories of all blobs and libraries
+    /// on disk, marking writable, missing and relative directories. Only
+    /// meaningful if `file` is the on-disk location of the binary, use `/` as
+    /// `sysroot` for the host system.
+    pub fn check_search_dirs(&mut self, sysroot: &Path) {
+        for blob in &mut self.blobs {
+            match &mut blob.properties {
+                #[cfg(feature = "elf")]
+                BinSpecificProperties::Elf(elf) => {
+                    elf.check_search_dirs(&self.file, sysroot);
+                }
+                #[cfg(feature = "macho")]
+                BinSpecificProperties::MachO(macho) => {
+                    macho.check_search_dirs(&self.file, sysroot);
+                }
+                _ => {}
+            }
+        }
+        for library in &mut self.libraries {
+            library.check_search_dirs(sysroot);
+        }
     }
 }
@@ -0,0 +1,166 @@
+//! Compare the properties of two binaries side by side
+//!
+//! Blobs are compared in order, field by field, using the property names of
+//! the JSON output. Only fields with different values are reported.
+use crate::binary::{BinSpecificProperties, BinType, Binary, Blob};
+use crate::policy::normalize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Property with different values, `Null` if a blob lacks the property
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct FieldDiff {
+    pub field: String,
+    pub left: Value,
+    pub right: Value,
+}
+
+/// Differing properties of a pair of blobs, a missing blob has no type
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct BlobComparison {
+    pub left: Option<BinType>,
+    pub right: Option<BinType>,
+    pub fields: Vec<FieldDiff>,
+}
+
+/// Side by side comparison of two binaries
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Comparison {
+    pub left: PathBuf,
+    pub right: PathBuf,
+    pub blobs: Vec<BlobComparison>,
+}
+
+impl Comparison {
+    /// Compare the blobs of two binaries in order.
+    #[must_use]
+    pub fn new(left: &Binary, right: &Binary) -> Self {
+        let count = left.blobs.len().max(right.blobs.len());
+        let blobs = (0..count)
+            .map(|index| {
+                compare_blobs(left.blobs.get(index), right.blobs.get(index))
+            })
+            .collect();
+        Self { left: left.file.clone(), right: right.file.clone(), blobs }
+    }
+
+    /// The binaries have no differing properties.
+    #[must_use]
+    pub fn is_equal(&self) -> bool {
+        self.blobs.iter().all(|blob| blob.fields.is_empty())
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let left = self.left.display().to_string();
+        let right = self.right.display().to_string();
+        for blob in &self.blobs {
+            writeln!(
+                f,
+                "{} ({}) vs {} ({})",
+                left,
+                display_type(blob.left.as_ref()),
+                right,
+                display_type(blob.right.as_ref())
+            )?;
+            if blob.fields.is_empty() {
+                writeln!(f, "  No differences")?;
+                continue;
+            }
+            let rows: Vec<[String; 3]> = blob
+                .fields
+                .iter()
+                .map(|field| {
+                    [
+                        field.field.clone(),
+                        display_value(&field.left),
+                        display_value(&field.right),
+                    ]
+                })
+                .collect();
+            let header = ["Property".to_string(), left.clone(), right.clone()];
+            let width = |column: usize| {
+                rows.iter()
+                    .chain(std::iter::once(&header))
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            };
+            let (field_width, left_width) = (width(0), width(1));
+            for row in std::iter::once(&header).chain(&rows) {
+                writeln!(
+                    f,
+                    "  {:field_width$}  {:left_width$}  {}",
+                    row[0], row[1], row[2]
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn compare_blobs(left: Option<&Blob>, right: Option<&Blob>) -> BlobComparison {
+    let left_fields = left.map(properties).unwrap_or_default();
+    let mut right_fields = right.map(properties).unwrap_or_default();
+    let mut fields: Vec<FieldDiff> = left_fields
+        .into_iter()
+        .filter_map(|(field, left)| {
+            let right = right_fields.remove(&field).unwrap_or(Value::Null);
+            (left != right).then_some(FieldDiff { field, left, right })
+        })
+        .collect();
+    fields.extend(right_fields.into_iter().map(|(field, right)| FieldDiff {
+        field,
+        left: Value::Null,
+        right,
+    }));
+    fields.sort_by(|a, b| a.field.cmp(&b.field));
+    BlobComparison {
+        left: left.map(|blob| blob.binarytype.clone()),
+        right: right.map(|blob| blob.binarytype.clone()),
+        fields,
+    }
+}
+
+// Properties of a blob by their JSON name, parse errors as `error`
+fn properties(blob: &Blob) -> Map<String, Value> {
+    let properties = match &blob.properties {
+        #[cfg(feature = "elf")]
+        BinSpecificProperties::Elf(elf) => serde_json::to_value(elf),
+        #[cfg(feature = "pe")]
+        BinSpecificProperties::PE(pe) => serde_json::to_value(pe),
+        #[cfg(feature = "macho")]
+        BinSpecificProperties::MachO(macho) => serde_json::to_value(macho),
+        BinSpecificProperties::Error(error) => {
+            Ok(Value::Object(Map::from_iter([(
+                "error".to_string(),
+                Value::String(error.message.clone()),
+            )])))
+        }
+    };
+    match properties {
+        Ok(Value::Object(properties)) => properties
+            .into_iter()
+            .map(|(field, value)| {
+                let value = normalize(&value);
+                (field, value)
+            })
+            .collect(),
+        _ => Map::new(),
+    }
+}
+
+fn display_type(binarytype: Option<&BinType>) -> String {
+    binarytype.map_or("-".to_string(), ToString::to_string)
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
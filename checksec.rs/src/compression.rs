@@ -1,4 +1,5 @@
 //! Utilities for compression and encoding of checksec reports
+use crate::error::Error;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use base64::Engine;
 use bincode::{
@@ -27,7 +28,7 @@ use std::io::{Cursor, Read, Write};
 ///
 /// Returns a `Result` containing:
 /// - `Ok(String)` with the base64-encoded compressed representation of the input data.
-/// - `Err(String)` describing the failure reason.
+/// - `Err(Error::Compression)` describing the failure reason.
 ///
 /// # Errors
 ///
@@ -47,18 +48,20 @@ use std::io::{Cursor, Read, Write};
 /// - `#[serde(untagged)]`
 ///
 /// See: <https://docs.rs/bincode/latest/bincode/serde/index.html#known-issues>
-pub fn compress<T: Serialize>(results: &T) -> Result<String, String> {
-    let serialized = encode_to_vec(results, config::standard())
-        .map_err(|_| "Result serialization to binary failed".to_string())?;
+pub fn compress<T: Serialize>(results: &T) -> Result<String, Error> {
+    let serialized =
+        encode_to_vec(results, config::standard()).map_err(|_| {
+            Error::Compression("Result serialization to binary failed")
+        })?;
 
     let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
     encoder
         .write_all(&serialized)
-        .map_err(|_| "Compression failed".to_string())?;
+        .map_err(|_| Error::Compression("Compression failed"))?;
 
     let compressed = encoder
         .finish()
-        .map_err(|_| "IO error occurred during flush".to_string())?;
+        .map_err(|_| Error::Compression("IO error occurred during flush"))?;
 
     let encoded_compressed = BASE64_STANDARD.encode(compressed);
     Ok(encoded_compressed) // original type -> serialized -> compressed -> B64
@@ -79,7 +82,7 @@ pub fn compress<T: Serialize>(results: &T) -> Result<String, String> {
 ///
 /// # Returns
 ///
-/// `Ok(T)` if successful, or `Err(String)` describing the failure.
+/// `Ok(T)` if successful, or `Err(Error::Compression)` describing the failure.
 ///
 /// # Errors
 ///
@@ -98,25 +101,25 @@ pub fn compress<T: Serialize>(results: &T) -> Result<String, String> {
 /// See: <https://docs.rs/bincode/latest/bincode/serde/index.html#known-issues>
 pub fn decompress<T: DeserializeOwned>(
     encoded_bytes: &[u8],
-) -> Result<T, String> {
+) -> Result<T, Error> {
     let encoded_compressed = std::str::from_utf8(encoded_bytes)
-        .map_err(|_| "Error converting bytes to utf".to_string())?
+        .map_err(|_| Error::Compression("Error converting bytes to utf"))?
         .to_string();
 
     let compressed = BASE64_STANDARD
         .decode(encoded_compressed)
-        .map_err(|_| "Decoding failed".to_string())?;
+        .map_err(|_| Error::Compression("Decoding failed"))?;
 
     let cursor = Cursor::new(compressed);
     let mut decoder = ZlibDecoder::new(cursor);
     let mut decompressed = Vec::new();
-    decoder
-        .read_to_end(&mut decompressed)
-        .map_err(|_| "Error occurred during decompression".to_string())?;
+    decoder.read_to_end(&mut decompressed).map_err(|_| {
+        Error::Compression("Error occurred during decompression")
+    })?;
 
     let (deserialized, _) =
         decode_from_slice(&decompressed, config::standard())
-            .map_err(|_| "Deserialization failed".to_string())?;
+            .map_err(|_| Error::Compression("Deserialization failed"))?;
 
     Ok(deserialized) // input bytes -> B64 -> bytes -> decompress -> deserialize
 }
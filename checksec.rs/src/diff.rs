@@ -0,0 +1,332 @@
+//! Compare two scans to find hardening regressions
+//!
+//! Binaries and their libraries are matched by path, then by their path
+//! relative to the directory of the scanned binaries, so scans of different
+//! directories (e.g. `release-1.0` and `release-1.1`) are compared binary by
+//! binary. Binaries which were moved without being modified are matched by
+//! their SHA-256 hash, or by the hash of their results for reports without
+//! file hashes, and are neither reported as new nor as removed.
+//!
+//! Checks are compared by their SARIF results: a check whose level got worse
+//! (e.g. from `none` to `warning`) lost a mitigation and is a regression, one
+//! whose level got better gained a mitigation.
+use crate::binary::{BinSpecificProperties, BinType, Binary, Blob};
+use crate::compression::get_sha256_hash;
+use crate::sarif::get_blob_results;
+use crate::severity::Level;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Changed result of a check
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PropertyChange {
+    pub binarytype: BinType,
+    /// Architecture of the fat Mach-O slice
+    #[serde(default)]
+    pub architecture: Option<String>,
+    /// SARIF rule id of the check
+    pub rule: String,
+    pub before: String,
+    pub after: String,
+    pub before_level: Level,
+    pub after_level: Level,
+}
+
+impl PropertyChange {
+    /// The check lost a mitigation.
+    #[must_use]
+    pub fn is_regression(&self) -> bool {
+        self.after_level > self.before_level
+    }
+
+    /// The check gained a mitigation.
+    #[must_use]
+    pub fn is_improvement(&self) -> bool {
+        self.after_level < self.before_level
+    }
+}
+
+impl fmt::Display for PropertyChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let change = if self.is_regression() {
+            "lost"
+        } else if self.is_improvement() {
+            "gained"
+        } else {
+            "changed"
+        };
+        match &self.architecture {
+            Some(architecture) => {
+                write!(f, "({} {}) ", self.binarytype, architecture)?;
+            }
+            None => write!(f, "({}) ", self.binarytype)?,
+        }
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.rule, change, self.before, self.after
+        )
+    }
+}
+
+/// Changed checks of a binary present in both scans
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct BinaryDiff {
+    pub file: PathBuf,
+    pub changes: Vec<PropertyChange>,
+}
+
+/// Differences between a baseline and a current scan
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct Diff {
+    /// Binaries only in the current scan
+    pub added: Vec<PathBuf>,
+    /// Binaries only in the baseline
+    pub removed: Vec<PathBuf>,
+    /// Binaries with changed checks
+    pub changed: Vec<BinaryDiff>,
+}
+
+/// A scan with its differences to a baseline scan, the JSON output of scans
+/// with a baseline. Its binaries can serve as the baseline of the next scan.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BaselineReport {
+    pub binaries: Vec<Binary>,
+    pub diff: Diff,
+}
+
+impl Diff {
+    /// Compare the binaries and libraries of a baseline and a current scan.
+    #[must_use]
+    pub fn new(baseline: &[Binary], current: &[Binary]) -> Self {
+        let matching = Matching::new(baseline, current);
+        let mut changed: Vec<BinaryDiff> = matching
+            .pairs
+            .iter()
+            .filter_map(|(before, after)| {
+                let changes = diff_blobs(&before.blobs, &after.blobs);
+                (!changes.is_empty())
+                    .then(|| BinaryDiff { file: after.file.clone(), changes })
+            })
+            .collect();
+        changed.sort_by(|left, right| left.file.cmp(&right.file));
+        Self {
+            added: files(&matching.added),
+            removed: files(&matching.removed),
+            changed,
+        }
+    }
+
+    /// Changes which lost a mitigation
+    pub fn regressions(
+        &self,
+    ) -> impl Iterator<Item = (&BinaryDiff, &PropertyChange)> {
+        self.changed.iter().flat_map(|binary| {
+            binary
+                .changes
+                .iter()
+                .filter(|change| change.is_regression())
+                .map(move |change| (binary, change))
+        })
+    }
+
+    #[must_use]
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.added {
+            writeln!(f, "New binary: {}", file.display())?;
+        }
+        for file in &self.removed {
+            writeln!(f, "Removed binary: {}", file.display())?;
+        }
+        for binary in &self.changed {
+            for change in &binary.changes {
+                writeln!(f, "{} {}", binary.file.display(), change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Binaries and libraries of a baseline and a current scan matched like in
+/// `Diff::new`
+#[derive(Debug, Default)]
+pub(crate) struct Matching<'a> {
+    /// The same binary in the baseline and in the current scan
+    pub pairs: Vec<(&'a Binary, &'a Binary)>,
+    /// Binaries only in the current scan
+    pub added: Vec<&'a Binary>,
+    /// Binaries only in the baseline
+    pub removed: Vec<&'a Binary>,
+}
+
+impl<'a> Matching<'a> {
+    pub fn new(baseline: &'a [Binary], current: &'a [Binary]) -> Self {
+        let baseline_root = scan_root(baseline);
+        let current_root = scan_root(current);
+        let mut baseline = flatten(baseline);
+        let mut matching = Self::default();
+        let mut unmatched = Vec::new();
+        for (file, binary) in flatten(current) {
+            match baseline.remove(&file) {
+                Some(before) => matching.pairs.push((before, binary)),
+                None => unmatched.push(binary),
+            }
+        }
+
+        // the same binaries in a scan of another directory
+        let mut baseline: BTreeMap<&Path, &Binary> = baseline
+            .into_values()
+            .map(|binary| (relative(&binary.file, &baseline_root), binary))
+            .collect();
+        let mut added = Vec::new();
+        for binary in unmatched {
+            match baseline.remove(relative(&binary.file, &current_root)) {
+                Some(before) => matching.pairs.push((before, binary)),
+                None => added.push(binary),
+            }
+        }
+
+        // unmodified binaries which were moved
+        let mut removed: Vec<(&Binary, Vec<u8>)> = baseline
+            .into_values()
+            .map(|binary| (binary, hash_blobs(&binary.blobs)))
+            .collect();
+        for binary in added {
+            let hash = hash_blobs(&binary.blobs);
+            let moved =
+                removed.iter().position(|(removed, blobs_hash)| {
+                    match (&removed.sha256, &binary.sha256) {
+                        (Some(before), Some(after)) => before == after,
+                        _ => *blobs_hash == hash,
+                    }
+                });
+            match moved {
+                Some(index) => {
+                    matching.pairs.push((removed.remove(index).0, binary));
+                }
+                None => matching.added.push(binary),
+            }
+        }
+        matching.removed =
+            removed.into_iter().map(|(binary, _)| binary).collect();
+        matching
+    }
+}
+
+// Sorted paths of binaries
+fn files(binaries: &[&Binary]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> =
+        binaries.iter().map(|binary| binary.file.clone()).collect();
+    files.sort();
+    files
+}
+
+// Deepest directory containing all scanned binaries
+fn scan_root(binaries: &[Binary]) -> PathBuf {
+    let mut dirs = binaries.iter().filter_map(|binary| binary.file.parent());
+    let Some(first) = dirs.next() else {
+        return PathBuf::new();
+    };
+    dirs.fold(first.to_path_buf(), |root, dir| {
+        root.ancestors()
+            .find(|ancestor| dir.starts_with(ancestor))
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    })
+}
+
+// Path of `file` below `root`, libraries outside of it keep their path
+fn relative<'a>(file: &'a Path, root: &Path) -> &'a Path {
+    file.strip_prefix(root).unwrap_or(file)
+}
+
+// All binaries and their libraries by path, the first occurrence of a
+// library wins
+fn flatten(binaries: &[Binary]) -> BTreeMap<PathBuf, &Binary> {
+    let mut flat = BTreeMap::new();
+    for binary in binaries {
+        flat.entry(binary.file.clone()).or_insert(binary);
+        for (file, library) in flatten(&binary.libraries) {
+            flat.entry(file).or_insert(library);
+        }
+    }
+    flat
+}
+
+fn hash_blobs(blobs: &[Blob]) -> Vec<u8> {
+    get_sha256_hash(&serde_json::to_vec(blobs).unwrap_or_default())
+}
+
+// Compare blobs of the same type and fat Mach-O slice architecture in order.
+// A binary which can no longer be parsed is a regression.
+fn diff_blobs(before: &[Blob], after: &[Blob]) -> Vec<PropertyChange> {
+    let mut before: Vec<&Blob> = before.iter().collect();
+    let mut changes = Vec::new();
+    for blob in after {
+        let Some(index) = before.iter().position(|old| {
+            old.binarytype == blob.binarytype
+                && architecture(old) == architecture(blob)
+        }) else {
+            if let BinSpecificProperties::Error(error) = &blob.properties {
+                changes.push(PropertyChange {
+                    binarytype: BinType::Error,
+                    architecture: architecture(blob),
+                    rule: "parse_error".to_string(),
+                    before: "Parsed".to_string(),
+                    after: error.message.clone(),
+                    before_level: Level::None,
+                    after_level: Level::Error,
+                });
+            }
+            continue;
+        };
+        changes.extend(diff_blob(before.remove(index), blob));
+    }
+    changes
+}
+
+fn architecture(blob: &Blob) -> Option<String> {
+    blob.slice.as_ref().map(|slice| slice.architecture.clone())
+}
+
+fn diff_blob(before: &Blob, after_blob: &Blob) -> Vec<PropertyChange> {
+    let mut before: BTreeMap<String, (String, Level)> =
+        get_blob_results(before)
+            .into_iter()
+            .filter_map(|result| {
+                Some((
+                    result.rule_id?,
+                    (result.message.text?, result.level?.into()),
+                ))
+            })
+            .collect();
+    get_blob_results(after_blob)
+        .into_iter()
+        .filter_map(|result| {
+            let rule = result.rule_id?;
+            let after = result.message.text?;
+            let after_level: Level = result.level?.into();
+            let (before, before_level) = before.remove(&rule)?;
+            if before == after && before_level == after_level {
+                return None;
+            }
+            Some(PropertyChange {
+                binarytype: after_blob.binarytype.clone(),
+                architecture: architecture(after_blob),
+                rule,
+                before,
+                after,
+                before_level,
+                after_level,
+            })
+        })
+        .collect()
+}
@@ -1,34 +1,58 @@
 //! Implements checksec for ELF binaries
 #[cfg(feature = "color")]
 use colored::Colorize;
-#[cfg(target_os = "linux")]
-use either::Either;
 use goblin::elf::dynamic::{
-    DF_1_NOW, DF_1_PIE, DF_BIND_NOW, DT_RPATH, DT_RUNPATH,
+    DF_1_NOW, DF_1_PIE, DF_BIND_NOW, DF_TEXTREL, DT_RPATH, DT_RUNPATH,
+};
+use goblin::elf::header::{
+    machine_to_str, EM_386, EM_AARCH64, EM_X86_64, ET_DYN, ET_REL,
 };
-use goblin::elf::header::{machine_to_str, ET_DYN, ET_REL};
-use goblin::elf::program_header::{PF_X, PT_GNU_RELRO, PT_GNU_STACK};
+use goblin::elf::note::{NT_GNU_BUILD_ID, NT_GNU_PROPERTY_TYPE_0};
+use goblin::elf::program_header::{
+    PF_W, PF_X, PT_GNU_RELRO, PT_GNU_STACK, PT_LOAD,
+};
+use goblin::elf::section_header::SHF_EXECINSTR;
 #[cfg(feature = "disassembly")]
-use goblin::elf::section_header::{SHF_ALLOC, SHF_EXECINSTR, SHT_PROGBITS};
+use goblin::elf::section_header::{SHF_ALLOC, SHT_PROGBITS};
 use goblin::elf::Elf;
+use scroll::{Endian, Pread};
 use serde_derive::{Deserialize, Serialize};
 #[cfg(feature = "disassembly")]
 use std::convert::TryFrom;
 use std::fmt;
+use std::path::Path;
 #[cfg(target_os = "linux")]
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 #[cfg(feature = "color")]
 use crate::colorize_bool;
 #[cfg(feature = "disassembly")]
 use crate::disassembly::{has_stack_clash_protection, Bitness};
 #[cfg(target_os = "linux")]
-use crate::ldso::{LdSoError, LdSoLookup};
-use crate::shared::{Endianness, Rpath, VecRpath};
+use crate::ldso::{is_matching_elf, LdSoError, LdSoLookup};
+use crate::shared::{binary_dir_in_sysroot, Endianness, Rpath, VecRpath};
 
 static STC_CANARY_KWDS: [&str; 3] =
     ["__stack_chk_fail", "__stack_chk_guard", "__intel_security_cookie"];
 
+// `pr_type` values and feature bits from the x86-64 psABI, AArch64 ELF ABI
+// and Linux ABI extensions for `NT_GNU_PROPERTY_TYPE_0` notes
+const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x1;
+const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x2;
+const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 0x1;
+const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 0x2;
+
+// sections only present in Linux kernel modules
+const KERNEL_MODULE_SECTIONS: [&str; 3] =
+    [".modinfo", "__versions", ".gnu.linkonce.this_module"];
+// trailer of a signed kernel module, see `scripts/sign-file.c`
+const MODULE_SIG_STRING: &[u8] = b"~Module signature appended~\n";
+// `endbr64` and `endbr32`
+const ENDBR64: [u8; 4] = [0xf3, 0x0f, 0x1e, 0xfa];
+const ENDBR32: [u8; 4] = [0xf3, 0x0f, 0x1e, 0xfb];
+
 /// Relocation Read-Only mode: `None`, `Partial`, or `Full`
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub enum Relro {
@@ -98,13 +122,15 @@ impl fmt::Display for Nx {
     }
 }
 
-/// Position Independent Executable mode: `None`, `DSO`, `REL` or `PIE`
+/// Position Independent Executable mode: `None`, `DSO`, `REL`, `PIE` or
+/// `Module` for Linux kernel modules
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub enum PIE {
     None,
     DSO,
     REL,
     PIE,
+    Module,
 }
 
 impl fmt::Display for PIE {
@@ -118,6 +144,7 @@ impl fmt::Display for PIE {
                 Self::DSO => "DSO",
                 Self::REL => "REL",
                 Self::PIE => "Full",
+                Self::Module => "Module",
             }
         )
     }
@@ -131,6 +158,7 @@ impl fmt::Display for PIE {
                 Self::DSO => "DSO".yellow(),
                 Self::REL => "REL".yellow(),
                 Self::PIE => "Full".green(),
+                Self::Module => "Module".green(),
             }
         )
     }
@@ -174,6 +202,126 @@ impl fmt::Display for Fortify {
     }
 }
 
+/// `PT_LOAD` segment that is mapped both writable and executable
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct RwxSegment {
+    /// Index into the program header table
+    pub index: usize,
+    /// Virtual address of the segment (`p_vaddr`)
+    pub vaddr: u64,
+    /// Size of the segment in memory (`p_memsz`)
+    pub memsz: u64,
+}
+
+impl fmt::Display for RwxSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] 0x{:x}-0x{:x}",
+            self.index,
+            self.vaddr,
+            self.vaddr.saturating_add(self.memsz)
+        )
+    }
+}
+
+#[cfg(not(feature = "color"))]
+fn format_rwx_segments(segments: &[RwxSegment]) -> String {
+    if segments.is_empty() {
+        return "None".to_string();
+    }
+    segments.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(feature = "color")]
+fn format_rwx_segments(segments: &[RwxSegment]) -> String {
+    if segments.is_empty() {
+        return "None".green().to_string();
+    }
+    segments
+        .iter()
+        .map(|segment| segment.to_string().red().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Hardening of a Linux kernel module (`.ko`)
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct KernelModule {
+    /// Module signature appended (`~Module signature appended~` trailer)
+    pub signed: bool,
+    /// `vermagic` of the kernel the module was built for
+    pub vermagic: Option<String>,
+    /// Built with retpolines (`retpoline=Y` in `.modinfo`)
+    pub retpoline: bool,
+    /// Kernel Control Flow Integrity (`__cfi_` symbols)
+    pub cfi: bool,
+    /// Indirect Branch Tracking (`endbr` at function entries)
+    pub ibt: bool,
+    /// Stack protector (`__stack_chk_fail` or `__stack_chk_guard`)
+    pub stack_protector: bool,
+    /// `license` of the module
+    pub license: Option<String>,
+}
+
+impl KernelModule {
+    /// Parse the module properties, `None` if the ELF is no kernel module.
+    #[must_use]
+    pub fn parse(elf: &Elf, bytes: &[u8]) -> Option<Self> {
+        if !elf.is_kernel_module() {
+            return None;
+        }
+        let has_symbol = |matches: &dyn Fn(&str) -> bool| {
+            elf.syms
+                .iter()
+                .any(|sym| elf.strtab.get_at(sym.st_name).is_some_and(matches))
+        };
+        Some(Self {
+            signed: bytes.ends_with(MODULE_SIG_STRING),
+            vermagic: elf.get_modinfo(bytes, "vermagic"),
+            retpoline: elf
+                .get_modinfo(bytes, "retpoline")
+                .is_some_and(|value| value == "Y"),
+            cfi: has_symbol(&|name| name.starts_with("__cfi_")),
+            ibt: elf.has_ibt(bytes) || elf.has_endbr(bytes),
+            stack_protector: has_symbol(&|name| {
+                STC_CANARY_KWDS.iter().any(|kw| name.contains(kw))
+            }),
+            license: elf.get_modinfo(bytes, "license"),
+        })
+    }
+}
+
+impl fmt::Display for KernelModule {
+    #[cfg(not(feature = "color"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Signed: {} Vermagic: {} Retpoline: {} License: {}",
+            self.signed,
+            self.vermagic.as_deref().unwrap_or("None").trim_end(),
+            self.retpoline,
+            self.license.as_deref().unwrap_or("None"),
+        )
+    }
+    #[cfg(feature = "color")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {}",
+            "Signed:".bold(),
+            colorize_bool!(self.signed),
+            "Vermagic:".bold(),
+            self.vermagic.as_deref().unwrap_or("None").trim_end(),
+            "Retpoline:".bold(),
+            colorize_bool!(self.retpoline),
+            "License:".bold(),
+            self.license.as_deref().unwrap_or("None"),
+        )
+    }
+}
+
 /// Checksec result struct for ELF32/64 binaries
 ///
 /// **Example**
@@ -211,14 +359,33 @@ pub struct CheckSecResults {
     pub clang_safestack: bool,
     /// Stack Clash Protection (*CFLAGS=*`-fstack-clash-protection`)
     pub stack_clash_protection: bool,
+    /// Intel CET Indirect Branch Tracking (*CFLAGS=*`-fcf-protection=branch`)
+    #[serde(default)]
+    pub ibt: bool,
+    /// Intel CET Shadow Stack (*CFLAGS=*`-fcf-protection=return`)
+    #[serde(default)]
+    pub shstk: bool,
+    /// `AArch64` Branch Target Identification
+    /// (*CFLAGS=*`-mbranch-protection=bti`)
+    #[serde(default)]
+    pub bti: bool,
+    /// `AArch64` Pointer Authentication (*CFLAGS=*`-mbranch-protection=pac-ret`)
+    #[serde(default)]
+    pub pac: bool,
     /// Fortify (*CFLAGS=*`-D_FORTIFY_SOURCE`)
     pub fortify: Fortify,
     /// Fortified functions
     pub fortified: u32,
     /// Fortifiable functions
     pub fortifiable: u32,
-    /// No Execute
+    /// No Execute, disabled by an executable stack or by `rwx_segments`
     pub nx: Nx,
+    /// Writable and executable `PT_LOAD` segments
+    #[serde(default)]
+    pub rwx_segments: Vec<RwxSegment>,
+    /// Text relocations (`DT_TEXTREL` or `DF_TEXTREL` in `DT_FLAGS`)
+    #[serde(default)]
+    pub textrel: bool,
     /// Position Inpendent Executable (*CFLAGS=*`-pie -fPIE`)
     pub pie: PIE,
     /// Relocation Read-Only
@@ -235,9 +402,26 @@ pub struct CheckSecResults {
     pub dynlibs: Vec<String>,
     // number of symbols
     pub symbol_count: usize,
+    /// Linux kernel module properties
+    #[serde(default)]
+    pub kernel_module: Option<KernelModule>,
 }
 
 impl CheckSecResults {
+    /// Check the RPATH and RUNPATH directories of the binary at `binarypath`
+    /// on disk after expanding dynamic string tokens, see
+    /// `Rpath::check_dir`. Use `/` as `sysroot` for the host system.
+    pub fn check_search_dirs(&mut self, binarypath: &Path, sysroot: &Path) {
+        let origin = binary_dir_in_sysroot(binarypath, sysroot)
+            .and_then(|dir| dir.to_str().map(String::from));
+        let is_64 = self.bitness == 64;
+        let expand = |entry: &str| {
+            expand_dst(entry, origin.as_deref(), &self.architecture, is_64)
+        };
+        self.rpath = self.rpath.check_dirs(sysroot, expand);
+        self.runpath = self.runpath.check_dirs(sysroot, expand);
+    }
+
     #[must_use]
     pub fn parse(elf: &Elf, bytes: &[u8]) -> Self {
         let (fortified, fortifiable) = elf.has_fortified();
@@ -248,6 +432,10 @@ impl CheckSecResults {
             (f, v) if f < v => Fortify::Partial,
             _ => Fortify::Undecidable, // This case should never happen
         };
+        let kernel_module = KernelModule::parse(elf, bytes);
+        let module_has = |property: fn(&KernelModule) -> bool| {
+            kernel_module.as_ref().is_some_and(property)
+        };
         Self {
             architecture: elf.get_architecture(),
             bitness: if elf.is_64 { 64 } else { 32 },
@@ -261,14 +449,20 @@ impl CheckSecResults {
                 Some(path) => path.to_string(),
                 None => "Unknown".into(),
             },
-            canary: elf.has_canary(),
-            clang_cfi: elf.has_clang_cfi(),
+            canary: elf.has_canary() || module_has(|m| m.stack_protector),
+            clang_cfi: elf.has_clang_cfi() || module_has(|m| m.cfi),
             clang_safestack: elf.has_clang_safestack(),
             fortify,
             fortified,
             fortifiable,
             stack_clash_protection: elf.has_stack_clash_protection(bytes),
+            ibt: elf.has_ibt(bytes) || module_has(|m| m.ibt),
+            shstk: elf.has_shstk(bytes),
+            bti: elf.has_bti(bytes),
+            pac: elf.has_pac(bytes),
             nx: elf.has_nx(),
+            rwx_segments: elf.get_rwx_segments(),
+            textrel: elf.has_textrel(),
             pie: elf.has_pie(),
             relro: elf.has_relro(),
             asan: elf.has_asan(),
@@ -281,6 +475,7 @@ impl CheckSecResults {
                 .map(std::string::ToString::to_string)
                 .collect(),
             symbol_count: elf.symbol_count(),
+            kernel_module,
         }
     }
 }
@@ -292,8 +487,8 @@ impl fmt::Display for CheckSecResults {
         write!(
             f,
             "Architecture: {} Bitness: {} Endianness: {} Dynamic Linking: {} Interpreter: {} \
-            Canary: {} CFI: {} SafeStack: {} StackClash: {} Fortify: {} Fortified: {:2} \
-            Fortifiable: {:2} NX: {} PIE: {} Relro: {} ASan: {} Seperate Code: {} RPATH: {} RUNPATH: {} Symbols: {}",
+            Canary: {} CFI: {} SafeStack: {} StackClash: {} IBT: {} SHSTK: {} BTI: {} PAC: {} Fortify: {} Fortified: {:2} \
+            Fortifiable: {:2} NX: {} RWX Segments: {} TEXTREL: {} PIE: {} Relro: {} ASan: {} Seperate Code: {} RPATH: {} RUNPATH: {} Symbols: {}",
             self.architecture,
             self.bitness,
             self.endianness,
@@ -303,10 +498,16 @@ impl fmt::Display for CheckSecResults {
             self.clang_cfi,
             self.clang_safestack,
             self.stack_clash_protection,
+            self.ibt,
+            self.shstk,
+            self.bti,
+            self.pac,
             self.fortify,
             self.fortified,
             self.fortifiable,
             self.nx,
+            format_rwx_segments(&self.rwx_segments),
+            self.textrel,
             self.pie,
             self.relro,
             self.asan,
@@ -314,14 +515,18 @@ impl fmt::Display for CheckSecResults {
             self.rpath,
             self.runpath,
             self.symbols,
-        )
+        )?;
+        if let Some(module) = &self.kernel_module {
+            write!(f, " {module}")?;
+        }
+        Ok(())
     }
     #[cfg(feature = "color")]
     /// Colorized human readable format output
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "{} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}",
+            "{} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}",
             "Architecture:".bold(),
             self.architecture,
             "Bitness:".bold(),
@@ -340,6 +545,14 @@ impl fmt::Display for CheckSecResults {
             colorize_bool!(self.clang_safestack),
             "StackClash:".bold(),
             colorize_bool!(self.stack_clash_protection),
+            "IBT:".bold(),
+            colorize_bool!(self.ibt),
+            "SHSTK:".bold(),
+            colorize_bool!(self.shstk),
+            "BTI:".bold(),
+            colorize_bool!(self.bti),
+            "PAC:".bold(),
+            colorize_bool!(self.pac),
             "Fortify:".bold(),
             self.fortify,
             "Fortified:".bold(),
@@ -348,6 +561,14 @@ impl fmt::Display for CheckSecResults {
             self.fortifiable,
             "NX:".bold(),
             self.nx,
+            "RWX Segments:".bold(),
+            format_rwx_segments(&self.rwx_segments),
+            "TEXTREL:".bold(),
+            if self.textrel {
+                format!("{:<5}", self.textrel).red()
+            } else {
+                format!("{:<5}", self.textrel).bright_green()
+            },
             "PIE:".bold(),
             self.pie,
             "Relro:".bold(),
@@ -362,7 +583,11 @@ impl fmt::Display for CheckSecResults {
             self.runpath,
             "Symbols".bold(),
             self.symbol_count,
-        )
+        )?;
+        if let Some(module) = &self.kernel_module {
+            write!(f, " {module}")?;
+        }
+        Ok(())
     }
 }
 
@@ -395,12 +620,33 @@ pub trait Properties {
     fn has_clang_safestack(&self) -> bool;
     /// checks for Stack Clash Protection
     fn has_stack_clash_protection(&self, bytes: &[u8]) -> bool;
+    /// check `GNU_PROPERTY_X86_FEATURE_1_IBT` in the
+    /// `GNU_PROPERTY_X86_FEATURE_1_AND` GNU property
+    fn has_ibt(&self, bytes: &[u8]) -> bool;
+    /// check `GNU_PROPERTY_X86_FEATURE_1_SHSTK` in the
+    /// `GNU_PROPERTY_X86_FEATURE_1_AND` GNU property
+    fn has_shstk(&self, bytes: &[u8]) -> bool;
+    /// check `GNU_PROPERTY_AARCH64_FEATURE_1_BTI` in the
+    /// `GNU_PROPERTY_AARCH64_FEATURE_1_AND` GNU property
+    fn has_bti(&self, bytes: &[u8]) -> bool;
+    /// check `GNU_PROPERTY_AARCH64_FEATURE_1_PAC` in the
+    /// `GNU_PROPERTY_AARCH64_FEATURE_1_AND` GNU property
+    fn has_pac(&self, bytes: &[u8]) -> bool;
+    /// return the `u32` data of the given `pr_type` from the
+    /// `NT_GNU_PROPERTY_TYPE_0` note (`PT_GNU_PROPERTY`/`.note.gnu.property`)
+    fn get_gnu_property(&self, bytes: &[u8], pr_type: u32) -> Option<u32>;
     /// check for symbols ending in `_chk` from dynstrtab
     fn has_fortify(&self) -> bool;
     /// counts fortified and fortifiable symbols from dynstrtab
     fn has_fortified(&self) -> (u32, u32);
-    /// check `p_flags` of the `PT_GNU_STACK` ELF header
+    /// check `p_flags` of the `PT_GNU_STACK` ELF header, NX is disabled by
+    /// writable and executable `PT_LOAD` segments as well
     fn has_nx(&self) -> Nx;
+    /// collect `PT_LOAD` program headers with both `PF_W` and `PF_X` set
+    fn get_rwx_segments(&self) -> Vec<RwxSegment>;
+    /// check for `DT_TEXTREL` or `DF_TEXTREL` in `DT_FLAGS` of the `.dynamic`
+    /// section
+    fn has_textrel(&self) -> bool;
     /// check `d_val` of `DT_FLAGS`/`DT_FLAGS_1` of the `PT_DYN ELF` header
     fn has_pie(&self) -> PIE;
     /// check `d_val` is `DF_BIND_NOW` for `DT_FLAGS`/`DT_FLAGS_1` of the
@@ -420,6 +666,15 @@ pub trait Properties {
     fn has_asan(&self) -> bool;
     // Check if the program headers are combined with the text section, resulting in
     fn has_seperate_code_section(&self) -> bool;
+    /// get the hex encoded `NT_GNU_BUILD_ID` note
+    fn get_build_id(&self, bytes: &[u8]) -> Option<String>;
+    /// check for a relocatable object with `.modinfo`, `__versions` or
+    /// `.gnu.linkonce.this_module` sections
+    fn is_kernel_module(&self) -> bool;
+    /// return the value of `key` from the `key=value` strings in `.modinfo`
+    fn get_modinfo(&self, bytes: &[u8], key: &str) -> Option<String>;
+    /// check for `endbr64`/`endbr32` at the entry of most x86 functions
+    fn has_endbr(&self, bytes: &[u8]) -> bool;
 }
 
 // readelf -s -W /lib/x86_64-linux-gnu/libc.so.6 | grep _chk
@@ -590,6 +845,72 @@ impl Properties for Elf<'_> {
                 }
             })
     }
+    fn has_ibt(&self, bytes: &[u8]) -> bool {
+        matches!(self.header.e_machine, EM_X86_64 | EM_386)
+            && self
+                .get_gnu_property(bytes, GNU_PROPERTY_X86_FEATURE_1_AND)
+                .is_some_and(|features| {
+                    features & GNU_PROPERTY_X86_FEATURE_1_IBT != 0
+                })
+    }
+    fn has_shstk(&self, bytes: &[u8]) -> bool {
+        matches!(self.header.e_machine, EM_X86_64 | EM_386)
+            && self
+                .get_gnu_property(bytes, GNU_PROPERTY_X86_FEATURE_1_AND)
+                .is_some_and(|features| {
+                    features & GNU_PROPERTY_X86_FEATURE_1_SHSTK != 0
+                })
+    }
+    fn has_bti(&self, bytes: &[u8]) -> bool {
+        self.header.e_machine == EM_AARCH64
+            && self
+                .get_gnu_property(bytes, GNU_PROPERTY_AARCH64_FEATURE_1_AND)
+                .is_some_and(|features| {
+                    features & GNU_PROPERTY_AARCH64_FEATURE_1_BTI != 0
+                })
+    }
+    fn has_pac(&self, bytes: &[u8]) -> bool {
+        self.header.e_machine == EM_AARCH64
+            && self
+                .get_gnu_property(bytes, GNU_PROPERTY_AARCH64_FEATURE_1_AND)
+                .is_some_and(|features| {
+                    features & GNU_PROPERTY_AARCH64_FEATURE_1_PAC != 0
+                })
+    }
+    fn get_gnu_property(&self, bytes: &[u8], pr_type: u32) -> Option<u32> {
+        // relocatable objects have no PT_NOTE, fall back to the section
+        let notes = self.iter_note_headers(bytes).or_else(|| {
+            self.iter_note_sections(bytes, Some(".note.gnu.property"))
+        })?;
+        let endian =
+            if self.little_endian { Endian::Little } else { Endian::Big };
+        // property arrays are padded to the native word size
+        let align = if self.is_64 { 8 } else { 4 };
+        for note in notes.flatten() {
+            if note.name != "GNU" || note.n_type != NT_GNU_PROPERTY_TYPE_0 {
+                continue;
+            }
+            let desc = note.desc;
+            let mut offset = 0;
+            while let (Ok(ptype), Ok(datasz)) = (
+                desc.pread_with::<u32>(offset, endian),
+                desc.pread_with::<u32>(offset + 4, endian),
+            ) {
+                let datasz = datasz as usize;
+                if ptype == pr_type && datasz == 4 {
+                    return desc.pread_with(offset + 8, endian).ok();
+                }
+                match datasz
+                    .checked_next_multiple_of(align)
+                    .and_then(|size| size.checked_add(offset + 8))
+                {
+                    Some(next) => offset = next,
+                    None => break,
+                }
+            }
+        }
+        None
+    }
     fn has_fortify(&self) -> bool {
         for sym in &self.dynsyms {
             if !sym.is_function() {
@@ -624,6 +945,9 @@ impl Properties for Elf<'_> {
         if self.program_headers.is_empty() {
             return Nx::Na;
         }
+        if !self.get_rwx_segments().is_empty() {
+            return Nx::Disabled;
+        }
         for header in &self.program_headers {
             if header.p_type == PT_GNU_STACK {
                 if PF_X != header.p_flags & PF_X {
@@ -634,6 +958,28 @@ impl Properties for Elf<'_> {
         }
         Nx::Disabled
     }
+    fn get_rwx_segments(&self) -> Vec<RwxSegment> {
+        self.program_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| {
+                header.p_type == PT_LOAD
+                    && header.p_flags & (PF_W | PF_X) == PF_W | PF_X
+            })
+            .map(|(index, header)| RwxSegment {
+                index,
+                vaddr: header.p_vaddr,
+                memsz: header.p_memsz,
+            })
+            .collect()
+    }
+    fn has_textrel(&self) -> bool {
+        if let Some(dynamic) = &self.dynamic {
+            return dynamic.info.textrel
+                || DF_TEXTREL & dynamic.info.flags == DF_TEXTREL;
+        }
+        false
+    }
     fn has_pie(&self) -> PIE {
         if self.header.e_type == ET_DYN {
             if let Some(dynamic) = &self.dynamic {
@@ -643,6 +989,9 @@ impl Properties for Elf<'_> {
             }
             return PIE::DSO;
         }
+        if self.is_kernel_module() {
+            return PIE::Module;
+        }
         if self.header.e_type == ET_REL {
             return PIE::REL;
         }
@@ -722,6 +1071,74 @@ impl Properties for Elf<'_> {
             .iter()
             .any(|header| header.p_offset == 0 && header.is_executable())
     }
+    fn get_build_id(&self, bytes: &[u8]) -> Option<String> {
+        // relocatable objects have no PT_NOTE, fall back to the section
+        let notes = self.iter_note_headers(bytes).or_else(|| {
+            self.iter_note_sections(bytes, Some(".note.gnu.build-id"))
+        })?;
+        notes
+            .flatten()
+            .find(|note| note.name == "GNU" && note.n_type == NT_GNU_BUILD_ID)
+            .map(|note| hex::encode(note.desc))
+    }
+    fn is_kernel_module(&self) -> bool {
+        self.header.e_type == ET_REL
+            && self.section_headers.iter().any(|sh| {
+                self.shdr_strtab
+                    .get_at(sh.sh_name)
+                    .is_some_and(|name| KERNEL_MODULE_SECTIONS.contains(&name))
+            })
+    }
+    fn get_modinfo(&self, bytes: &[u8], key: &str) -> Option<String> {
+        let modinfo = self.section_headers.iter().find(|sh| {
+            self.shdr_strtab.get_at(sh.sh_name) == Some(".modinfo")
+        })?;
+        let data = bytes.get(modinfo.file_range()?)?;
+        data.split(|byte| *byte == 0).find_map(|entry| {
+            let value =
+                entry.strip_prefix(key.as_bytes())?.strip_prefix(b"=")?;
+            Some(String::from_utf8_lossy(value).into_owned())
+        })
+    }
+    fn has_endbr(&self, bytes: &[u8]) -> bool {
+        if !matches!(self.header.e_machine, EM_X86_64 | EM_386) {
+            return false;
+        }
+        // entries of functions in executable sections, `true` for `endbr`
+        let entries: Vec<bool> = self
+            .syms
+            .iter()
+            .filter(goblin::elf::Sym::is_function)
+            .filter_map(|sym| {
+                let sh = self.section_headers.get(sym.st_shndx)?;
+                if sh.sh_flags & u64::from(SHF_EXECINSTR) == 0 {
+                    return None;
+                }
+                // symbol values of relocatable objects are section offsets
+                let offset = if self.header.e_type == ET_REL {
+                    sh.sh_offset.checked_add(sym.st_value)
+                } else {
+                    sym.st_value
+                        .checked_sub(sh.sh_addr)
+                        .and_then(|value| value.checked_add(sh.sh_offset))
+                };
+                Some(
+                    offset
+                        .and_then(|offset| usize::try_from(offset).ok())
+                        .and_then(|offset| {
+                            bytes.get(offset..offset.checked_add(4)?)
+                        })
+                        .is_some_and(|insn| {
+                            insn == ENDBR64 || insn == ENDBR32
+                        }),
+                )
+            })
+            .collect();
+        // local functions which are only called directly may lack `endbr`,
+        // a single `endbr` among many functions is no IBT build
+        let endbr = entries.iter().filter(|endbr| **endbr).count();
+        endbr > 0 && endbr * 2 > entries.len()
+    }
 }
 
 #[cfg(target_os = "linux")]
@@ -740,55 +1157,148 @@ impl LibraryLookup {
         Ok(Self { ldsolookup: LdSoLookup::gen_lookup_dirs()? })
     }
 
+    /// Initialize a library lookup handle for Elf files of the root
+    /// filesystem at `sysroot`. The ld.so configuration, RPATH/RUNPATH
+    /// entries and interpreters are resolved inside `sysroot`.
+    ///
+    /// # Errors
+    /// Will fail if the ld.so.conf configuration can not be read or has an
+    /// invalid format.
+    pub fn with_sysroot(sysroot: &Path) -> Result<Self, LdSoError> {
+        Ok(Self { ldsolookup: LdSoLookup::gen_lookup_dirs_in(sysroot)? })
+    }
+
+    /// Resolve the program interpreter (`PT_INTERP`) of a binary.
+    #[must_use]
+    pub fn interpreter(&self, elf: &CheckSecResults) -> Option<PathBuf> {
+        if !elf.interpreter.starts_with('/') {
+            return None;
+        }
+        self.ldsolookup.resolve(Path::new(&elf.interpreter))
+    }
+
+    /// Resolve a `DT_NEEDED` entry of the binary at `binarypath` in the order
+    /// of glibc's dynamic loader: `DT_RPATH` (ignored if there is a
+    /// `DT_RUNPATH`), `DT_RUNPATH`, then `LdSoLookup::search`. Libraries of
+    /// other architectures or bitness are skipped.
     #[must_use]
     pub fn lookup(
         &self,
         binarypath: &Path,
-        rpath: &VecRpath,
-        runpath: &VecRpath,
+        elf: &CheckSecResults,
         libfilename: &str,
     ) -> Option<PathBuf> {
-        let parentbinpath = if let Some(parent) = binarypath.parent() {
-            parent.to_str()
-        } else {
-            None
+        let origin = self.origin(binarypath);
+        let is_64 = elf.bitness == 64;
+        let search = |paths: &VecRpath| {
+            search_path_entries(paths)
+                .filter_map(|entry| {
+                    expand_dst(
+                        entry,
+                        origin.as_deref(),
+                        &elf.architecture,
+                        is_64,
+                    )
+                })
+                .filter_map(|dir| {
+                    self.ldsolookup.resolve(&Path::new(&dir).join(libfilename))
+                })
+                .find(|path| is_matching_elf(path, &elf.architecture, is_64))
         };
 
-        for rpath in rpath.iter().filter_map(|rpath| match rpath {
-            Rpath::YesRW(ref str) | Rpath::Yes(ref str) => Some(str),
-            Rpath::None => None,
-        }) {
-            let rpath = if let Some(p) = parentbinpath {
-                Either::Left(rpath.replace("$ORIGIN", p))
-            } else {
-                Either::Right(rpath)
-            };
-            let path = Path::new(&rpath).join(libfilename);
-            if path.is_file() {
-                return Some(path);
-            }
-        }
+        let has_runpath = search_path_entries(&elf.runpath).next().is_some();
+        (!has_runpath)
+            .then(|| search(&elf.rpath))
+            .flatten()
+            .or_else(|| search(&elf.runpath))
+            .or_else(|| {
+                self.ldsolookup.search(libfilename, &elf.architecture, is_64)
+            })
+    }
 
-        if let Some(path) = self.ldsolookup.search(libfilename) {
-            return Some(path);
-        }
+    fn origin(&self, binarypath: &Path) -> Option<String> {
+        binary_dir_in_sysroot(binarypath, self.ldsolookup.sysroot())
+            .and_then(|dir| dir.to_str().map(String::from))
+    }
+}
 
-        for runpath in runpath.iter().filter_map(|rpath| match rpath {
-            Rpath::YesRW(ref str) | Rpath::Yes(ref str) => Some(str),
+#[cfg(target_os = "linux")]
+fn search_path_entries(paths: &VecRpath) -> impl Iterator<Item = &str> {
+    paths.iter().filter_map(Rpath::path)
+}
 
-            Rpath::None => None,
-        }) {
-            let runpath = if let Some(p) = parentbinpath {
-                Either::Left(runpath.replace("$ORIGIN", p))
-            } else {
-                Either::Right(runpath)
-            };
-            let path = Path::new(&runpath).join(libfilename);
-            if path.is_file() {
-                return Some(path);
-            }
+const DST_NAMES: [&str; 3] = ["ORIGIN", "PLATFORM", "LIB"];
+
+/// Expand the dynamic string tokens `$ORIGIN`, `$PLATFORM` and `$LIB`, also
+/// in their `${...}` form, of a `DT_RPATH`/`DT_RUNPATH` entry like glibc's
+/// dynamic loader. Other `$` sequences are kept verbatim.
+///
+/// `$LIB` expands to the upstream glibc default for the target, distributions
+/// using multiarch directories may differ. Returns `None` if a token has no
+/// value (no `origin` or a CPU specific `$PLATFORM`), the loader ignores such
+/// entries.
+#[must_use]
+pub fn expand_dst(
+    entry: &str,
+    origin: Option<&str>,
+    architecture: &str,
+    is_64: bool,
+) -> Option<String> {
+    let mut expanded = String::with_capacity(entry.len());
+    let mut rest = entry;
+    while let Some(idx) = rest.find('$') {
+        expanded.push_str(&rest[..idx]);
+        let token = &rest[idx + 1..];
+        if let Some((name, len)) = parse_dst(token) {
+            expanded.push_str(match name {
+                "ORIGIN" => origin?,
+                "PLATFORM" => dst_platform(architecture, is_64)?,
+                _ => dst_lib(architecture, is_64),
+            });
+            rest = &token[len..];
+        } else {
+            expanded.push('$');
+            rest = token;
         }
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
 
-        None
+// Match a dynamic string token following a `$`, returns the token name and
+// the length of the consumed input. Unbraced names must not be followed by
+// an identifier character.
+fn parse_dst(input: &str) -> Option<(&'static str, usize)> {
+    let (braced, input) = match input.strip_prefix('{') {
+        Some(input) => (true, input),
+        None => (false, input),
+    };
+    DST_NAMES.iter().find_map(|&name| {
+        let tail = input.strip_prefix(name)?;
+        if braced {
+            tail.starts_with('}').then_some((name, name.len() + 2))
+        } else {
+            (!tail
+                .starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_'))
+            .then_some((name, name.len()))
+        }
+    })
+}
+
+// `AT_PLATFORM` of the target, `None` if it depends on the CPU
+fn dst_platform(architecture: &str, is_64: bool) -> Option<&'static str> {
+    match (architecture, is_64) {
+        ("X86_64", _) => Some("x86_64"),
+        ("386", false) => Some("i686"),
+        ("AARCH64", true) => Some("aarch64"),
+        _ => None,
+    }
+}
+
+fn dst_lib(architecture: &str, is_64: bool) -> &'static str {
+    match (architecture, is_64) {
+        ("X86_64", false) => "libx32",
+        (_, true) => "lib64",
+        (_, false) => "lib",
     }
 }
@@ -0,0 +1,194 @@
+//! Errors of the checksec library
+//!
+//! `Error` keeps the underlying errors for callers, `ErrorInfo` is its
+//! serializable form used for blobs which failed to parse and for the WASM
+//! bindings.
+#[cfg(target_os = "linux")]
+use crate::ldso::LdSoError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, io};
+
+/// Error while reading or analyzing a binary
+#[derive(Debug)]
+pub enum Error {
+    /// The binary is malformed
+    Goblin(goblin::error::Error),
+    /// The data ends before a structure it refers to
+    Truncated(goblin::error::Error),
+    /// The file is no supported binary format, `file_type` is the detected
+    /// type of the file, e.g. `zip`
+    UnsupportedFormat { file_type: String },
+    /// Support for the binary format is not compiled in
+    Unimplemented(&'static str),
+    /// A member of an archive or fat binary can not be extracted
+    Archive(String),
+    /// A report can not be compressed or decompressed
+    Compression(&'static str),
+    /// I/O error
+    IO(io::Error),
+    /// The library lookup can not be initialized
+    #[cfg(target_os = "linux")]
+    LdSo(LdSoError),
+}
+
+impl Error {
+    /// Unsupported format error with the file type detected from `buffer`.
+    #[must_use]
+    pub fn unsupported_format(buffer: &[u8]) -> Self {
+        Self::UnsupportedFormat {
+            file_type: infer::get(buffer)
+                .map_or("Unknown", |kind| kind.extension())
+                .to_string(),
+        }
+    }
+
+    /// Kind of the error
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Goblin(_) => ErrorKind::Malformed,
+            Self::Truncated(_) => ErrorKind::Truncated,
+            Self::UnsupportedFormat { .. } => ErrorKind::UnsupportedFormat,
+            Self::Unimplemented(_) => ErrorKind::Unimplemented,
+            Self::Archive(_) => ErrorKind::Archive,
+            Self::Compression(_) => ErrorKind::Compression,
+            Self::IO(_) => ErrorKind::IO,
+            #[cfg(target_os = "linux")]
+            Self::LdSo(_) => ErrorKind::LdSo,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Goblin(err) => err.fmt(f),
+            Self::Truncated(err) => write!(f, "Truncated binary: {err}"),
+            Self::UnsupportedFormat { file_type } => {
+                write!(f, "Unsupported File Format (File Type: {file_type})")
+            }
+            Self::Unimplemented(format) => {
+                write!(f, "Support for files of type {format} not implemented")
+            }
+            Self::Archive(msg) => msg.fmt(f),
+            Self::Compression(msg) => msg.fmt(f),
+            Self::IO(err) => err.fmt(f),
+            #[cfg(target_os = "linux")]
+            Self::LdSo(err) => {
+                write!(f, "Failed to initialize library lookup: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<goblin::error::Error> for Error {
+    fn from(err: goblin::error::Error) -> Self {
+        match err {
+            goblin::error::Error::BufferTooShort(..)
+            | goblin::error::Error::Scroll(
+                scroll::Error::TooBig { .. } | scroll::Error::BadOffset(_),
+            ) => Self::Truncated(err),
+            err => Self::Goblin(err),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::IO(err)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl From<LdSoError> for Error {
+    fn from(err: LdSoError) -> Self {
+        Self::LdSo(err)
+    }
+}
+
+/// Kind of an `Error`
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ErrorKind {
+    Malformed,
+    Truncated,
+    UnsupportedFormat,
+    Unimplemented,
+    Archive,
+    Compression,
+    IO,
+    LdSo,
+}
+
+/// Serializable form of an `Error`
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(remote = "Self")]
+pub struct ErrorInfo {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Detected file type of unsupported formats
+    pub file_type: Option<String>,
+}
+
+impl Serialize for ErrorInfo {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorInfo {
+    /// Older JSON reports have the message of a goblin error as a plain
+    /// string, binary formats always have the struct.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Legacy(String),
+            Info(#[serde(with = "ErrorInfo")] ErrorInfo),
+        }
+
+        if !deserializer.is_human_readable() {
+            return Self::deserialize(deserializer);
+        }
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Legacy(message) => {
+                Self { kind: ErrorKind::Malformed, message, file_type: None }
+            }
+            Repr::Info(info) => info,
+        })
+    }
+}
+
+impl From<&Error> for ErrorInfo {
+    fn from(err: &Error) -> Self {
+        Self {
+            kind: err.kind(),
+            message: err.to_string(),
+            file_type: match err {
+                Error::UnsupportedFormat { file_type } => {
+                    Some(file_type.clone())
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+impl From<Error> for ErrorInfo {
+    fn from(err: Error) -> Self {
+        Self::from(&err)
+    }
+}
+
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
@@ -0,0 +1,658 @@
+//! Check the hardening options of a Linux kernel
+//!
+//! Reads a kernel build configuration (`.config`, or the gzip compressed
+//! `/proc/config.gz` of the running kernel) and optionally a sysctl snapshot,
+//! either the output of `sysctl -a` or a `/proc/sys` tree, and compares them
+//! against recommended hardening options.
+//!
+//! ```rust
+//! use checksec::kernel::{KernelConfig, KernelReport};
+//!
+//! let config = KernelConfig::parse(
+//!     "CONFIG_STACKPROTECTOR_STRONG=y\n# CONFIG_COMPAT_BRK is not set\n",
+//! );
+//! let report = KernelReport::new(&config, None);
+//! assert!(report.checks.iter().any(|check| check.passed()));
+//! ```
+#[cfg(feature = "color")]
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
+
+use crate::error::Error;
+use crate::severity::Level;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Where the value of a check comes from
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Source {
+    /// Kernel build configuration
+    Config,
+    /// Runtime sysctl setting
+    Sysctl,
+}
+
+/// Recommended value of an option
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expected {
+    /// Built in (`=y`)
+    Enabled,
+    /// Not set or `=n`
+    Disabled,
+    /// Numeric value of at least the given one
+    AtLeast(i64),
+    /// Exactly the given value
+    Value(&'static str),
+}
+
+impl Expected {
+    fn matches(self, value: Option<&str>) -> bool {
+        match self {
+            Self::Enabled => value == Some("y"),
+            Self::Disabled => value.is_none_or(|value| value == "n"),
+            Self::AtLeast(minimum) => value
+                .and_then(parse_number)
+                .is_some_and(|value| value >= minimum),
+            Self::Value(expected) => value == Some(expected),
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Enabled => write!(f, "y"),
+            Self::Disabled => write!(f, "is not set"),
+            Self::AtLeast(minimum) => write!(f, ">= {minimum}"),
+            Self::Value(value) => value.fmt(f),
+        }
+    }
+}
+
+/// Recommended hardening option
+pub struct Check {
+    /// Option name, followed by older names of the same option
+    pub names: &'static [&'static str],
+    pub source: Source,
+    pub expected: Expected,
+    /// Config option of the architecture the check applies to
+    pub arch: Option<&'static str>,
+    pub description: &'static str,
+    /// Level of a SARIF result when the check fails
+    pub level: Level,
+}
+
+impl Check {
+    /// Name of the option, used as SARIF rule id
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.names[0]
+    }
+
+    /// How to satisfy the check
+    #[must_use]
+    pub fn remediation(&self) -> String {
+        let name = self.name();
+        match (self.source, self.expected) {
+            (Source::Config, Expected::Enabled) => {
+                format!("Build the kernel with {name}=y.")
+            }
+            (Source::Config, Expected::Disabled) => {
+                format!("Build the kernel without {name}.")
+            }
+            (Source::Config, expected) => {
+                format!("Build the kernel with {name} {expected}.")
+            }
+            (Source::Sysctl, Expected::AtLeast(minimum)) => {
+                format!("Set {name} to {minimum} or higher.")
+            }
+            (Source::Sysctl, expected) => {
+                format!("Set {name} = {expected}.")
+            }
+        }
+    }
+}
+
+/// Recommended kernel hardening options
+pub const CHECKS: &[Check] = &[
+    Check {
+        names: &[
+            "CONFIG_STACKPROTECTOR_STRONG",
+            "CONFIG_CC_STACKPROTECTOR_STRONG",
+        ],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Stack canaries for functions with local buffers",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_FORTIFY_SOURCE"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Compile and run time bounds checks of string functions",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_RANDOMIZE_BASE"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Kernel address space layout randomization (KASLR)",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_STRICT_KERNEL_RWX", "CONFIG_DEBUG_RODATA"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Kernel text and read-only data are not writable",
+        level: Level::Error,
+    },
+    Check {
+        names: &["CONFIG_STRICT_MODULE_RWX", "CONFIG_DEBUG_SET_MODULE_RONX"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Module text and read-only data are not writable",
+        level: Level::Error,
+    },
+    Check {
+        names: &["CONFIG_VMAP_STACK"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Kernel stacks with guard pages",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_HARDENED_USERCOPY"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Bounds checks of copies between kernel and user space",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_SLAB_FREELIST_HARDENED"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Obfuscated slab allocator freelist pointers",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_SLAB_FREELIST_RANDOM"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Randomized slab allocator freelist order",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_INIT_ON_ALLOC_DEFAULT_ON"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Zero initialized heap allocations",
+        level: Level::Note,
+    },
+    Check {
+        names: &["CONFIG_BUG_ON_DATA_CORRUPTION"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Kernel BUG on corrupted data structures",
+        level: Level::Note,
+    },
+    Check {
+        names: &["CONFIG_SECCOMP_FILTER"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "System call filtering with seccomp BPF programs",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_MODULE_SIG"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Kernel module signature verification",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_MODULE_SIG_FORCE"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Only signed kernel modules can be loaded",
+        level: Level::Note,
+    },
+    Check {
+        names: &["CONFIG_STRICT_DEVMEM"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: None,
+        description: "Restricted access to physical memory through /dev/mem",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_DEVKMEM"],
+        source: Source::Config,
+        expected: Expected::Disabled,
+        arch: None,
+        description: "No access to kernel memory through /dev/kmem",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_COMPAT_BRK"],
+        source: Source::Config,
+        expected: Expected::Disabled,
+        arch: None,
+        description: "Randomized heap start (brk) for all programs",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_DEFAULT_MMAP_MIN_ADDR"],
+        source: Source::Config,
+        expected: Expected::AtLeast(4096),
+        arch: None,
+        description: "Lowest address user space can map, against NULL \
+            pointer dereferences",
+        level: Level::Warning,
+    },
+    Check {
+        names: &[
+            "CONFIG_MITIGATION_PAGE_TABLE_ISOLATION",
+            "CONFIG_PAGE_TABLE_ISOLATION",
+        ],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: Some("CONFIG_X86"),
+        description: "Kernel page table isolation against Meltdown",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_MITIGATION_RETPOLINE", "CONFIG_RETPOLINE"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: Some("CONFIG_X86"),
+        description: "Retpolines against Spectre variant 2",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_X86_KERNEL_IBT"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: Some("CONFIG_X86"),
+        description: "Intel CET Indirect Branch Tracking in the kernel",
+        level: Level::Note,
+    },
+    Check {
+        names: &["CONFIG_ARM64_PTR_AUTH_KERNEL", "CONFIG_ARM64_PTR_AUTH"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: Some("CONFIG_ARM64"),
+        description: "Pointer authentication of kernel return addresses",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["CONFIG_ARM64_BTI_KERNEL"],
+        source: Source::Config,
+        expected: Expected::Enabled,
+        arch: Some("CONFIG_ARM64"),
+        description: "Branch Target Identification in the kernel",
+        level: Level::Note,
+    },
+    Check {
+        names: &["kernel.randomize_va_space"],
+        source: Source::Sysctl,
+        expected: Expected::Value("2"),
+        arch: None,
+        description: "Full address space layout randomization of processes",
+        level: Level::Error,
+    },
+    Check {
+        names: &["kernel.kptr_restrict"],
+        source: Source::Sysctl,
+        expected: Expected::AtLeast(1),
+        arch: None,
+        description: "Kernel pointers are hidden from unprivileged users",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["kernel.dmesg_restrict"],
+        source: Source::Sysctl,
+        expected: Expected::Value("1"),
+        arch: None,
+        description: "Kernel log is hidden from unprivileged users",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["kernel.yama.ptrace_scope"],
+        source: Source::Sysctl,
+        expected: Expected::AtLeast(1),
+        arch: None,
+        description: "ptrace is restricted to descendants of the tracer",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["kernel.unprivileged_bpf_disabled"],
+        source: Source::Sysctl,
+        expected: Expected::AtLeast(1),
+        arch: None,
+        description: "Unprivileged users can not load BPF programs",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["kernel.perf_event_paranoid"],
+        source: Source::Sysctl,
+        expected: Expected::AtLeast(2),
+        arch: None,
+        description: "Unprivileged users can not profile the kernel",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["net.core.bpf_jit_harden"],
+        source: Source::Sysctl,
+        expected: Expected::Value("2"),
+        arch: None,
+        description: "Constant blinding of JIT compiled BPF programs",
+        level: Level::Note,
+    },
+    Check {
+        names: &["fs.protected_symlinks"],
+        source: Source::Sysctl,
+        expected: Expected::Value("1"),
+        arch: None,
+        description: "Symlinks in world-writable sticky directories are \
+            restricted",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["fs.protected_hardlinks"],
+        source: Source::Sysctl,
+        expected: Expected::Value("1"),
+        arch: None,
+        description: "Hardlinks to files of other users are restricted",
+        level: Level::Warning,
+    },
+    Check {
+        names: &["fs.suid_dumpable"],
+        source: Source::Sysctl,
+        expected: Expected::Value("0"),
+        arch: None,
+        description: "Set-user-ID programs do not dump core",
+        level: Level::Warning,
+    },
+];
+
+// decimal or hexadecimal (`0x`) value
+fn parse_number(value: &str) -> Option<i64> {
+    match value.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+// read a file, decompressing gzip compressed files like /proc/config.gz
+fn read_text(path: &Path) -> Result<String, Error> {
+    let bytes = fs::read(path)?;
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return Ok(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    let mut text = String::new();
+    GzDecoder::new(bytes.as_slice()).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Kernel build configuration
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct KernelConfig {
+    pub file: PathBuf,
+    /// Option values without quotes, `n` for options which are not set
+    pub options: BTreeMap<String, String>,
+}
+
+impl KernelConfig {
+    /// Parse the `CONFIG_FOO=value` and `# CONFIG_FOO is not set` lines of
+    /// a kernel configuration.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let options = content
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| {
+                if let Some(name) = line
+                    .strip_prefix("# ")
+                    .and_then(|line| line.strip_suffix(" is not set"))
+                {
+                    return Some((name.to_string(), "n".to_string()));
+                }
+                let (name, value) = line.split_once('=')?;
+                name.starts_with("CONFIG_").then(|| {
+                    (name.to_string(), value.trim_matches('"').to_string())
+                })
+            })
+            .collect();
+        Self { file: PathBuf::new(), options }
+    }
+
+    /// Read and parse a kernel configuration, gzip compressed or not, see
+    /// `KernelConfig::parse`.
+    ///
+    /// # Errors
+    /// Will fail if the file can not be read or decompressed.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        Ok(Self { file: path.to_path_buf(), ..Self::parse(&read_text(path)?) })
+    }
+
+    /// Value of the first of `names` which is present
+    #[must_use]
+    pub fn get(&self, names: &[&str]) -> Option<&str> {
+        names
+            .iter()
+            .find_map(|name| self.options.get(*name))
+            .map(String::as_str)
+    }
+}
+
+/// Snapshot of sysctl settings
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Sysctl {
+    pub file: PathBuf,
+    pub settings: BTreeMap<String, String>,
+}
+
+impl Sysctl {
+    /// Parse `key = value` lines, e.g. the output of `sysctl -a`.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let settings = content
+            .lines()
+            .filter(|line| !line.starts_with(['#', ';']))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| {
+                (key.trim().replace('/', "."), value.trim().to_string())
+            })
+            .collect();
+        Self { file: PathBuf::new(), settings }
+    }
+
+    /// Read a snapshot file, see `Sysctl::parse`, or the settings of the
+    /// checks from a `/proc/sys` tree.
+    ///
+    /// # Errors
+    /// Will fail if the file or directory can not be read.
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        if !path.is_dir() {
+            return Ok(Self {
+                file: path.to_path_buf(),
+                ..Self::parse(&read_text(path)?)
+            });
+        }
+        // settings of disabled kernel features are missing
+        let settings = CHECKS
+            .iter()
+            .filter(|check| check.source == Source::Sysctl)
+            .filter_map(|check| {
+                let file = path.join(check.name().replace('.', "/"));
+                let value = fs::read_to_string(file).ok()?;
+                Some((check.name().to_string(), value.trim().to_string()))
+            })
+            .collect();
+        Ok(Self { file: path.to_path_buf(), settings })
+    }
+}
+
+/// Outcome of a check
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// The sysctl snapshot lacks the setting
+    Unknown,
+}
+
+impl fmt::Display for Status {
+    #[cfg(not(feature = "color"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<7}",
+            match self {
+                Self::Pass => "Pass",
+                Self::Fail => "Fail",
+                Self::Unknown => "Unknown",
+            }
+        )
+    }
+    #[cfg(feature = "color")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<7}",
+            match self {
+                Self::Pass => "Pass".green(),
+                Self::Fail => "Fail".red(),
+                Self::Unknown => "Unknown".yellow(),
+            }
+        )
+    }
+}
+
+/// Result of a hardening check
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct KernelCheck {
+    /// Option name, see `Check::name`
+    pub name: String,
+    pub source: Source,
+    pub description: String,
+    pub expected: String,
+    /// Current value, `None` if the option is not set
+    pub value: Option<String>,
+    pub status: Status,
+}
+
+impl KernelCheck {
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.status == Status::Pass
+    }
+}
+
+impl fmt::Display for KernelCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<40} {:<12} (expected {}) {}",
+            self.status,
+            self.name,
+            self.value.as_deref().unwrap_or("not set"),
+            self.expected,
+            self.description
+        )
+    }
+}
+
+/// Results of the hardening checks of a kernel
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct KernelReport {
+    /// Kernel configuration file
+    pub config: PathBuf,
+    /// Sysctl snapshot, sysctl settings are not checked without one
+    pub sysctl: Option<PathBuf>,
+    pub checks: Vec<KernelCheck>,
+}
+
+impl KernelReport {
+    /// Check `config` and `sysctl` against the recommended options for the
+    /// architecture of the kernel.
+    #[must_use]
+    pub fn new(config: &KernelConfig, sysctl: Option<&Sysctl>) -> Self {
+        let checks = CHECKS
+            .iter()
+            .filter(|check| {
+                check.arch.is_none_or(|arch| config.get(&[arch]) == Some("y"))
+            })
+            .filter_map(|check| {
+                let (value, status) = match check.source {
+                    Source::Config => {
+                        let value = config.get(check.names);
+                        (value, check.expected.matches(value))
+                    }
+                    Source::Sysctl => {
+                        let value = sysctl?.settings.get(check.name());
+                        let Some(value) = value else {
+                            return Some((check, None, Status::Unknown));
+                        };
+                        (
+                            Some(value.as_str()),
+                            check.expected.matches(Some(value)),
+                        )
+                    }
+                };
+                let status = if status { Status::Pass } else { Status::Fail };
+                Some((check, value, status))
+            })
+            .map(|(check, value, status)| KernelCheck {
+                name: check.name().to_string(),
+                source: check.source,
+                description: check.description.to_string(),
+                expected: check.expected.to_string(),
+                value: value.map(str::to_string),
+                status,
+            })
+            .collect();
+        Self {
+            config: config.file.clone(),
+            sysctl: sysctl.map(|sysctl| sysctl.file.clone()),
+            checks,
+        }
+    }
+
+    /// Checks which failed
+    pub fn failures(&self) -> impl Iterator<Item = &KernelCheck> {
+        self.checks.iter().filter(|check| check.status == Status::Fail)
+    }
+}
+
+impl fmt::Display for KernelReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Kernel configuration: {}", self.config.display())?;
+        if let Some(sysctl) = &self.sysctl {
+            writeln!(f, "Sysctl settings: {}", sysctl.display())?;
+        }
+        for check in &self.checks {
+            writeln!(f, "{check}")?;
+        }
+        Ok(())
+    }
+}
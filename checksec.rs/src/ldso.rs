@@ -1,25 +1,299 @@
 use crate::ldso::LdSoError::{IncludeDepth, InvalidFormat};
+use crate::shared::{resolve_in_sysroot, sysroot_join};
+use goblin::elf::header::machine_to_str;
+use scroll::{Endian, Pread};
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::{fmt, fs, io};
 
+const CACHEMAGIC: &[u8] = b"ld.so-1.7.0";
+const CACHEMAGIC_NEW: &[u8] = b"glibc-ld.so.cache1.1";
+// sizes of `struct cache_file`/`struct file_entry` and their new variants
+const CACHE_HEADER_SIZE: usize = 16;
+const CACHE_ENTRY_SIZE: usize = 12;
+const CACHE_HEADER_SIZE_NEW: usize = 48;
+const CACHE_ENTRY_SIZE_NEW: usize = 24;
+// `struct cache_file_new` contains an `u64`
+const CACHE_ALIGN_NEW: usize = 8;
+const FLAG_ELF: i32 = 0x0001;
+const FLAG_ELF_LIBC6: i32 = 0x0003;
+const FLAG_ENDIAN_MASK: u8 = 0x03;
+const FLAG_ENDIAN_LITTLE: u8 = 0x02;
+const FLAG_ENDIAN_BIG: u8 = 0x03;
+
 #[derive(Clone)]
 pub struct LdSoLookup {
+    sysroot: PathBuf,
+    cache: LdSoCache,
     lookup_dirs: Vec<PathBuf>,
 }
 
 impl LdSoLookup {
+    /// Directory all absolute paths are resolved in, `/` for the host system.
+    #[must_use]
+    pub fn sysroot(&self) -> &Path {
+        &self.sysroot
+    }
+
+    /// Resolve an absolute path inside the sysroot, see `resolve_in_sysroot`.
     #[must_use]
-    pub fn search(&self, filename: &str) -> Option<PathBuf> {
-        for dir in &self.lookup_dirs {
-            let path = dir.join(filename);
-            if path.is_file() {
-                return Some(path);
+    pub fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        resolve_in_sysroot(&self.sysroot, path)
+    }
+
+    /// Search a library the way the dynamic loader does after the
+    /// `DT_RPATH`/`DT_RUNPATH` entries: first in `ld.so.cache`, then in the
+    /// ld.so.conf directories and finally in the default trusted directories.
+    ///
+    /// Only ELF files matching `architecture` (as reported by
+    /// `goblin::elf::header::machine_to_str`) and the ELF class are returned.
+    #[must_use]
+    pub fn search(
+        &self,
+        filename: &str,
+        architecture: &str,
+        is_64: bool,
+    ) -> Option<PathBuf> {
+        if let Some(path) = self
+            .cache
+            .candidates(filename, architecture, is_64)
+            .filter_map(|path| self.resolve(path))
+            .find(|path| is_matching_elf(path, architecture, is_64))
+        {
+            return Some(path);
+        }
+        self.lookup_dirs
+            .iter()
+            .cloned()
+            .chain(default_dirs(architecture, is_64))
+            .filter_map(|dir| self.resolve(&dir.join(filename)))
+            .find(|path| is_matching_elf(path, architecture, is_64))
+    }
+}
+
+/// Entry of the dynamic loader cache (`/etc/ld.so.cache`)
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LdSoCacheEntry {
+    /// `FLAG_*` type and required ABI of the library
+    pub flags: i32,
+    /// Name under which the library is looked up
+    pub soname: String,
+    /// Location of the library
+    pub path: PathBuf,
+}
+
+/// Parsed dynamic loader cache in either the old (`ld.so-1.7.0`) or new
+/// (`glibc-ld.so.cache1.1`) format
+#[derive(Clone, Debug, Default)]
+pub struct LdSoCache {
+    entries: Vec<LdSoCacheEntry>,
+}
+
+impl LdSoCache {
+    /// Parse the contents of an `ld.so.cache` file.
+    ///
+    /// The new format is preferred if the cache contains both formats.
+    ///
+    /// # Errors
+    /// Will fail if the cache has an unknown magic or is truncated.
+    pub fn parse(bytes: &[u8]) -> Result<Self, LdSoError> {
+        if bytes.starts_with(CACHEMAGIC_NEW) {
+            return Self::parse_new(bytes);
+        }
+        if !bytes.starts_with(CACHEMAGIC) {
+            return Err(InvalidFormat("Unknown ld.so.cache format".into()));
+        }
+        let nlibs =
+            read_u32(bytes, CACHEMAGIC.len() + 1, Endian::default())? as usize;
+        let strings = nlibs
+            .checked_mul(CACHE_ENTRY_SIZE)
+            .and_then(|size| size.checked_add(CACHE_HEADER_SIZE))
+            .ok_or_else(|| InvalidFormat("Invalid ld.so.cache size".into()))?;
+        // glibc >= 2.2 appends the new format to the old one
+        let new_offset = strings.next_multiple_of(CACHE_ALIGN_NEW);
+        if let Some(new) = bytes.get(new_offset..) {
+            if new.starts_with(CACHEMAGIC_NEW) {
+                return Self::parse_new(new);
             }
         }
-        None
+        let string_table = bytes.get(strings..).ok_or_else(|| {
+            InvalidFormat("Truncated ld.so.cache entries".into())
+        })?;
+        let mut entries = Vec::with_capacity(nlibs);
+        for idx in 0..nlibs {
+            let offset = CACHE_HEADER_SIZE + idx * CACHE_ENTRY_SIZE;
+            entries.push(LdSoCacheEntry {
+                flags: read_u32(bytes, offset, Endian::default())?
+                    .cast_signed(),
+                soname: read_str(
+                    string_table,
+                    read_u32(bytes, offset + 4, Endian::default())?,
+                )?,
+                path: PathBuf::from(read_str(
+                    string_table,
+                    read_u32(bytes, offset + 8, Endian::default())?,
+                )?),
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    fn parse_new(bytes: &[u8]) -> Result<Self, LdSoError> {
+        let endian = match bytes.get(28).map(|flags| flags & FLAG_ENDIAN_MASK)
+        {
+            Some(FLAG_ENDIAN_LITTLE) => Endian::Little,
+            Some(FLAG_ENDIAN_BIG) => Endian::Big,
+            _ => Endian::default(),
+        };
+        let nlibs = read_u32(bytes, CACHEMAGIC_NEW.len(), endian)? as usize;
+        let mut entries = Vec::with_capacity(nlibs.min(bytes.len()));
+        for idx in 0..nlibs {
+            let offset = idx
+                .checked_mul(CACHE_ENTRY_SIZE_NEW)
+                .and_then(|offset| offset.checked_add(CACHE_HEADER_SIZE_NEW))
+                .ok_or_else(|| {
+                    InvalidFormat("Invalid ld.so.cache size".into())
+                })?;
+            // string offsets are relative to the new format header
+            entries.push(LdSoCacheEntry {
+                flags: read_u32(bytes, offset, endian)?.cast_signed(),
+                soname: read_str(bytes, read_u32(bytes, offset + 4, endian)?)?,
+                path: PathBuf::from(read_str(
+                    bytes,
+                    read_u32(bytes, offset + 8, endian)?,
+                )?),
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Read and parse a `ld.so.cache` file.
+    ///
+    /// # Errors
+    /// Will fail if the file can not be read or is not a valid cache.
+    pub fn from_file(path: &Path) -> Result<Self, LdSoError> {
+        Self::parse(&fs::read(path)?)
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[LdSoCacheEntry] {
+        &self.entries
+    }
+
+    /// Paths of all cache entries for `soname` which are usable by an ELF
+    /// of the given architecture and class, in cache order.
+    pub fn candidates<'a>(
+        &'a self,
+        soname: &'a str,
+        architecture: &str,
+        is_64: bool,
+    ) -> impl Iterator<Item = &'a Path> {
+        let ids = cache_ids(architecture, is_64);
+        self.entries
+            .iter()
+            .filter(move |entry| {
+                entry.soname == soname
+                    && (entry.flags == FLAG_ELF
+                        || ids.is_none_or(|ids| ids.contains(&entry.flags)))
+            })
+            .map(|entry| entry.path.as_path())
+    }
+}
+
+fn read_u32(
+    bytes: &[u8],
+    offset: usize,
+    endian: Endian,
+) -> Result<u32, LdSoError> {
+    bytes
+        .pread_with(offset, endian)
+        .map_err(|_| InvalidFormat("Truncated ld.so.cache".into()))
+}
+
+fn read_str(bytes: &[u8], offset: u32) -> Result<String, LdSoError> {
+    bytes
+        .get(offset as usize..)
+        .and_then(|tail| tail.split(|&c| c == 0).next())
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .ok_or_else(|| InvalidFormat("Invalid ld.so.cache string".into()))
+}
+
+// `_DL_CACHE_DEFAULT_ID` values the dynamic loader of the given target accepts
+// in addition to `FLAG_ELF`, `None` if any library may match.
+fn cache_ids(architecture: &str, is_64: bool) -> Option<&'static [i32]> {
+    Some(match (architecture, is_64) {
+        ("X86_64", true) => &[0x0303],
+        ("X86_64", false) => &[0x0803],
+        ("AARCH64", true) => &[0x0a03],
+        ("ARM", false) => &[0x0903, 0x0b03],
+        ("PPC64", true) => &[0x0503],
+        ("S390", true) => &[0x0403],
+        ("SPARCV9", true) => &[0x0103],
+        ("IA_64", true) => &[0x0203],
+        ("MIPS", false) => &[FLAG_ELF_LIBC6, 0x0c03],
+        ("MIPS", true) => &[0x0603, 0x0703, 0x0d03, 0x0e03],
+        ("RISCV", _) => &[0x0f03, 0x1003],
+        ("LOONGARCH", true) => &[0x1103, 0x1203],
+        ("386" | "PPC" | "S390" | "SPARC", false) => &[FLAG_ELF_LIBC6],
+        _ => return None,
+    })
+}
+
+// Built-in trusted directories of the dynamic loader, including the
+// Debian-style multiarch directories for the given target.
+fn default_dirs(architecture: &str, is_64: bool) -> Vec<PathBuf> {
+    let triplets: &[&str] = match (architecture, is_64) {
+        ("X86_64", true) => &["x86_64-linux-gnu"],
+        ("X86_64", false) => &["x86_64-linux-gnux32"],
+        ("386", false) => &["i386-linux-gnu"],
+        ("AARCH64", true) => &["aarch64-linux-gnu"],
+        ("ARM", false) => &["arm-linux-gnueabihf", "arm-linux-gnueabi"],
+        ("PPC64", true) => &["powerpc64le-linux-gnu", "powerpc64-linux-gnu"],
+        ("S390", true) => &["s390x-linux-gnu"],
+        ("RISCV", true) => &["riscv64-linux-gnu"],
+        ("LOONGARCH", true) => &["loongarch64-linux-gnu"],
+        _ => &[],
+    };
+    let libdir = match (architecture, is_64) {
+        ("X86_64", false) => "libx32",
+        (_, true) => "lib64",
+        (_, false) => "lib32",
+    };
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for prefix in ["/lib", "/usr/lib"] {
+        dirs.extend(triplets.iter().map(|t| Path::new(prefix).join(t)));
+    }
+    dirs.push(Path::new("/").join(libdir));
+    dirs.push(Path::new("/usr").join(libdir));
+    dirs.push(PathBuf::from("/lib"));
+    dirs.push(PathBuf::from("/usr/lib"));
+    dirs
+}
+
+// Check the ELF identification and `e_machine` of a candidate library, the
+// dynamic loader skips files built for a different target.
+pub(crate) fn is_matching_elf(
+    path: &Path,
+    architecture: &str,
+    is_64: bool,
+) -> bool {
+    let mut header = [0_u8; 20];
+    let Ok(mut file) = fs::File::open(path) else {
+        return false;
+    };
+    if file.read_exact(&mut header).is_err() || !header.starts_with(b"\x7fELF")
+    {
+        return false;
     }
+    let endian = if header[5] == 2 { Endian::Big } else { Endian::Little };
+    let Ok(machine) = header.pread_with::<u16>(18, endian) else {
+        return false;
+    };
+    header[4] == if is_64 { 2 } else { 1 }
+        && machine_to_str(machine) == architecture
 }
 
+#[derive(Debug)]
 pub enum LdSoError {
     /// I/O error
     IO(io::Error),
@@ -58,7 +332,10 @@ impl From<io::Error> for LdSoError {
 }
 
 impl LdSoLookup {
+    // `conffile` is a host path, the returned directories are relative to
+    // `sysroot`.
     fn parse_ldso_conf_file(
+        sysroot: &Path,
         conffile: &Path,
         include_depth: u8,
     ) -> Result<Vec<PathBuf>, LdSoError> {
@@ -82,7 +359,19 @@ impl LdSoLookup {
                         "Invalid include path: {include_path}"
                     )));
                 }
-                for file in glob::glob(include_path).map_err(|e| {
+                let sysroot_pattern = sysroot.to_str().ok_or_else(|| {
+                    InvalidFormat(format!(
+                        "Invalid sysroot path: {}",
+                        sysroot.display()
+                    ))
+                })?;
+                let pattern = format!(
+                    "{}{include_path}",
+                    glob::Pattern::escape(
+                        sysroot_pattern.trim_end_matches('/')
+                    )
+                );
+                for file in glob::glob(&pattern).map_err(|e| {
                     LdSoError::Pattern(e, PathBuf::from(include_path))
                 })? {
                     let file = file.map_err(|e| {
@@ -90,6 +379,7 @@ impl LdSoLookup {
                     })?;
                     lookup_paths.append(
                         &mut LdSoLookup::parse_ldso_conf_file(
+                            sysroot,
                             &file,
                             include_depth + 1,
                         )?,
@@ -113,16 +403,41 @@ impl LdSoLookup {
         Ok(lookup_paths)
     }
 
-    /// Initialize a lookup handle from the ld.so.conf configuration of the
-    /// system.
+    /// Initialize a lookup handle from the ld.so.cache and the ld.so.conf
+    /// configuration of the system.
     ///
     /// # Errors
     /// Will fail if the ld.so.conf configuration can not be read or has an
-    /// invalid format.
+    /// invalid format, or if an existing ld.so.cache is malformed.
     pub fn gen_lookup_dirs() -> Result<LdSoLookup, LdSoError> {
+        LdSoLookup::gen_lookup_dirs_in(Path::new("/"))
+    }
+
+    /// Initialize a lookup handle from the ld.so.cache and the ld.so.conf
+    /// configuration of the root filesystem at `sysroot`, e.g. an extracted
+    /// container image or firmware.
+    ///
+    /// # Errors
+    /// Will fail if the ld.so.conf configuration can not be read or has an
+    /// invalid format, or if an existing ld.so.cache is malformed.
+    pub fn gen_lookup_dirs_in(
+        sysroot: &Path,
+    ) -> Result<LdSoLookup, LdSoError> {
+        let cache = match LdSoCache::from_file(&sysroot_join(
+            sysroot,
+            Path::new("/etc/ld.so.cache"),
+        )) {
+            Err(LdSoError::IO(e)) if e.kind() == io::ErrorKind::NotFound => {
+                LdSoCache::default()
+            }
+            cache => cache?,
+        };
         Ok(LdSoLookup {
+            sysroot: sysroot.to_path_buf(),
+            cache,
             lookup_dirs: LdSoLookup::parse_ldso_conf_file(
-                Path::new("/etc/ld.so.conf"),
+                sysroot,
+                &sysroot_join(sysroot, Path::new("/etc/ld.so.conf")),
                 0,
             )?,
         })
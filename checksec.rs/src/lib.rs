@@ -1,12 +1,15 @@
 #![warn(clippy::pedantic)]
-use goblin::mach::{Mach, MultiArch, SingleArch::Archive, SingleArch::MachO};
+#[cfg(feature = "macho")]
+use goblin::mach::{Mach, MachO, MultiArch, SingleArch};
 use goblin::Object;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "disassembly")]
 pub mod disassembly;
 #[cfg(feature = "elf")]
 pub mod elf;
+pub mod error;
+pub mod kernel;
 #[cfg(target_os = "linux")]
 pub mod ldso;
 #[cfg(feature = "macho")]
@@ -15,13 +18,27 @@ pub mod macros;
 pub mod output;
 #[cfg(feature = "pe")]
 pub mod pe;
+pub mod policy;
+pub mod proc;
 #[cfg(feature = "shared")]
 #[macro_use]
 pub mod shared;
 pub mod binary;
+pub mod compare;
 pub mod compression;
+pub mod diff;
 pub mod sarif;
+pub mod severity;
+#[cfg(feature = "macho")]
+use binary::FatSlice;
 use binary::{BinSpecificProperties, BinType, Binary, Blob};
+#[cfg(feature = "elf")]
+use elf::Properties as _;
+use error::Error;
+#[cfg(feature = "macho")]
+use macho::Properties as _;
+#[cfg(feature = "pe")]
+use pe::Properties as _;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -65,13 +82,93 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[must_use]
 pub fn checksec(bytes: &[u8], filename: String) -> Binary {
     Binary::new(PathBuf::from(filename), get_blob_from_buf(bytes))
+        .with_contents(bytes)
+}
+
+/// Analyze a file buffer like `checksec`, keeping the members of static
+/// libraries apart.
+///
+/// The CLI and the WASM bindings use this function, so a file gives the same
+/// results everywhere. The first `Binary` holds the slices of a fat Mach-O,
+/// followed by one `Binary` per archive member named `file➔member`, or
+/// `file➔architecture➔member` for the static library slices of a fat Mach-O.
+/// A file which can not be parsed yields a single `Binary` with an error blob.
+///
+/// # Example
+/// ```rust
+/// use std::{fs, path::Path};
+/// use checksec::checksec_binaries;
+///
+/// let file = Path::new("tests/binaries/elf/all");
+/// let buffer = fs::read(file).expect("Failed to read binary");
+/// let binaries = checksec_binaries(&buffer, file);
+/// assert_eq!(binaries.len(), 1);
+/// assert_eq!(binaries[0].file, file);
+/// ```
+#[must_use]
+pub fn checksec_binaries(bytes: &[u8], file: &Path) -> Vec<Binary> {
+    try_checksec_binaries(bytes, file).unwrap_or_else(|err| {
+        vec![Binary::new(file.to_path_buf(), vec![Blob::error(&err)])
+            .with_contents(bytes)]
+    })
+}
+
+/// Analyze a file buffer like `checksec_binaries`, but fail if the file is no
+/// supported binary. Archive members and fat Mach-O slices which can not be
+/// parsed are still returned with error blobs.
+///
+/// # Errors
+/// Will fail if the buffer is malformed, truncated or no supported format.
+pub fn try_checksec_binaries(
+    bytes: &[u8],
+    file: &Path,
+) -> Result<Vec<Binary>, Error> {
+    match Object::parse(bytes)? {
+        #[cfg(feature = "elf")]
+        Object::Elf(elf) => {
+            let result = elf::CheckSecResults::parse(&elf, bytes);
+            let bin_type =
+                if elf.is_64 { BinType::Elf64 } else { BinType::Elf32 };
+            let blob = Blob::new(bin_type, BinSpecificProperties::Elf(result))
+                .with_build_id(elf.get_build_id(bytes));
+            Ok(vec![Binary::new(file.to_path_buf(), vec![blob])
+                .with_contents(bytes)])
+        }
+        #[cfg(feature = "pe")]
+        Object::PE(pe) => {
+            let result = pe::CheckSecResults::parse(&pe, bytes);
+            let bin_type =
+                if pe.is_64 { BinType::PE64 } else { BinType::PE32 };
+            let blob = Blob::new(bin_type, BinSpecificProperties::PE(result))
+                .with_build_id(pe.get_pdb_guid());
+            Ok(vec![Binary::new(file.to_path_buf(), vec![blob])
+                .with_contents(bytes)])
+        }
+        #[cfg(feature = "macho")]
+        Object::Mach(mach) => match mach {
+            Mach::Binary(mach) => Ok(vec![Binary::new(
+                file.to_path_buf(),
+                vec![macho_blob(&mach)],
+            )
+            .with_contents(bytes)]),
+            Mach::Fat(mach) => Ok(process_fat_mach(&mach, bytes, file)),
+        },
+        #[cfg(not(feature = "elf"))]
+        Object::Elf(_) => Err(Error::Unimplemented("ELF")),
+        #[cfg(not(feature = "pe"))]
+        Object::PE(_) => Err(Error::Unimplemented("PE")),
+        #[cfg(not(feature = "macho"))]
+        Object::Mach(_) => Err(Error::Unimplemented("MachO")),
+        Object::Archive(archive) => Ok(parse_archive(&archive, bytes, file)),
+        _ => Err(Error::unsupported_format(bytes)),
+    }
 }
 
 /// Parses a binary buffer and performs security analysis based on the detected format.
 ///
 /// Supports ELF, PE, and Mach-O binaries (both 32-bit and 64-bit). Returns multiple
-/// `Blob` objects for fat Mach-O binaries, single `Blob` for other formats.
-/// Errors are returned as `Blob` objects with `BinType::Error`.
+/// `Blob` objects for fat Mach-O binaries and static libraries, single `Blob` for
+/// other formats. Errors are returned as `Blob` objects with `BinType::Error`.
 ///
 /// # Arguments
 /// * `buffer` - Raw binary data to analyze
@@ -90,121 +187,130 @@ pub fn checksec(bytes: &[u8], filename: String) -> Binary {
 /// ```
 #[must_use]
 pub fn get_blob_from_buf(buffer: &[u8]) -> Vec<Blob> {
-    match Object::parse(buffer) {
-        Ok(Object::Elf(elf)) => {
-            let result = elf::CheckSecResults::parse(&elf, buffer);
-            let bin_type =
-                if elf.is_64 { BinType::Elf64 } else { BinType::Elf32 };
-            vec![Blob::new(bin_type, BinSpecificProperties::Elf(result))]
-        }
-        Ok(Object::PE(pe)) => {
-            let result = pe::CheckSecResults::parse(&pe, buffer);
-            let bin_type =
-                if pe.is_64 { BinType::PE64 } else { BinType::PE32 };
-            vec![Blob::new(bin_type, BinSpecificProperties::PE(result))]
-        }
-        Ok(Object::Mach(mach)) => match mach {
-            Mach::Binary(mach) => {
-                let result = macho::CheckSecResults::parse(&mach);
-                let bin_type = if mach.is_64 {
-                    BinType::MachO64
-                } else {
-                    BinType::MachO32
-                };
-                vec![Blob::new(bin_type, BinSpecificProperties::MachO(result))]
-            }
-            Mach::Fat(mach) => process_fat_mach(&mach, buffer),
-        },
-        Ok(Object::Unknown(_)) => {
-            vec![Blob::new(
-                BinType::Error,
-                BinSpecificProperties::Error(format!(
-                    "Unsupported File Format (File Type: {})",
-                    get_file_type(buffer)
-                )),
-            )]
-        }
-        Err(res) => vec![Blob::new(
-            BinType::Error,
-            BinSpecificProperties::Error(res.to_string()),
-        )],
-        _ => vec![Blob::new(
-            BinType::Error,
-            BinSpecificProperties::Error("unsupported file type".to_string()),
-        )],
-    }
+    try_get_blob_from_buf(buffer).unwrap_or_else(|err| vec![Blob::error(&err)])
+}
+
+/// Parses a binary buffer like `get_blob_from_buf`, but fails if the buffer
+/// is no supported binary. The blobs of all binaries returned by
+/// `try_checksec_binaries` are merged, e.g. those of all archive members.
+///
+/// # Errors
+/// Will fail if the buffer is malformed, truncated or no supported format.
+///
+/// # Example
+/// ```rust
+/// use checksec::{error::ErrorKind, try_get_blob_from_buf};
+///
+/// let err = try_get_blob_from_buf(&[0; 64]).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::UnsupportedFormat);
+/// ```
+pub fn try_get_blob_from_buf(buffer: &[u8]) -> Result<Vec<Blob>, Error> {
+    Ok(try_checksec_binaries(buffer, Path::new(""))?
+        .into_iter()
+        .flat_map(|binary| binary.blobs)
+        .collect())
+}
+
+#[cfg(feature = "macho")]
+fn macho_blob(mach: &MachO) -> Blob {
+    let result = macho::CheckSecResults::parse(mach);
+    let bin_type =
+        if mach.is_64 { BinType::MachO64 } else { BinType::MachO32 };
+    Blob::new(bin_type, BinSpecificProperties::MachO(result))
+        .with_build_id(mach.get_uuid())
 }
 
 // Parse out the individual binaries/artifacts contained in a multi-architectural binary.
-fn process_fat_mach(fatmach: &MultiArch, bytes: &[u8]) -> Vec<Blob> {
+// Members of static library slices are named after the file and architecture
+// of the slice, e.g. `libfoo.a➔arm64➔foo.o`.
+#[cfg(feature = "macho")]
+fn process_fat_mach(
+    fatmach: &MultiArch,
+    bytes: &[u8],
+    file: &Path,
+) -> Vec<Binary> {
     let mut blob_vec: Vec<Blob> = Vec::new();
+    let mut members: Vec<Binary> = Vec::new();
     for (idx, fatarch) in fatmach.iter_arches().enumerate() {
-        if let Ok(container) = fatmach.get(idx) {
-            match container {
-                MachO(mach) => {
-                    let result = macho::CheckSecResults::parse(&mach);
-                    let bin_type = if mach.is_64 {
-                        BinType::MachO64
-                    } else {
-                        BinType::MachO32
-                    };
-                    blob_vec.push(Blob::new(
-                        bin_type,
-                        BinSpecificProperties::MachO(result),
+        let Ok(container) = fatmach.get(idx) else {
+            continue;
+        };
+        let Ok(fatarch) = fatarch else {
+            blob_vec.push(Blob::error(&Error::Archive(
+                "fatarch enumeration failed".to_string(),
+            )));
+            continue;
+        };
+        let slice = FatSlice::from(&fatarch);
+        match container {
+            SingleArch::MachO(mach) => {
+                blob_vec.push(macho_blob(&mach).with_slice(slice));
+            }
+            SingleArch::Archive(archive) => {
+                if let Some(archive_bytes) = bytes.get(
+                    fatarch.offset as usize
+                        ..(fatarch.offset + fatarch.size) as usize,
+                ) {
+                    let slice_file = PathBuf::from(format!(
+                        "{}\u{2794}{}",
+                        file.display(),
+                        slice.architecture
                     ));
+                    members.extend(
+                        parse_archive(&archive, archive_bytes, &slice_file)
+                            .into_iter()
+                            .map(|mut member| {
+                                for blob in &mut member.blobs {
+                                    blob.slice = Some(slice.clone());
+                                }
+                                member
+                            }),
+                    );
+                } else {
+                    blob_vec.push(
+                        Blob::error(&Error::Archive(
+                            "Archive refers to invalid position".to_string(),
+                        ))
+                        .with_slice(slice),
+                    );
                 }
-                Archive(archive) => match fatarch {
-                    Ok(fatarch) => {
-                        if let Some(archive_bytes) = bytes.get(
-                            fatarch.offset as usize
-                                ..(fatarch.offset + fatarch.size) as usize,
-                        ) {
-                            blob_vec.extend(parse_archive(
-                                &archive,
-                                archive_bytes,
-                            ));
-                        } else {
-                            blob_vec.push(Blob::new(
-                                BinType::Error,
-                                BinSpecificProperties::Error(
-                                    "Archive refers to invalid position"
-                                        .to_string(),
-                                ),
-                            ));
-                        }
-                    }
-                    _ => blob_vec.push(Blob::new(
-                        BinType::Error,
-                        BinSpecificProperties::Error(
-                            "fatarch enumeration failed".to_string(),
-                        ),
-                    )),
-                },
             }
         }
     }
-    blob_vec
+    // a fat static library only consists of archive members
+    if blob_vec.is_empty() && !members.is_empty() {
+        return members;
+    }
+    let mut binaries =
+        vec![Binary::new(file.to_path_buf(), blob_vec).with_contents(bytes)];
+    binaries.append(&mut members);
+    binaries
 }
 
 // Parse out binaries contained in an archive/static library.
 fn parse_archive(
     archive: &goblin::archive::Archive,
     bytes: &[u8],
-) -> Vec<Blob> {
+    file: &Path,
+) -> Vec<Binary> {
     archive
         .members()
         .iter()
-        .filter_map(|member_name| match archive.extract(member_name, bytes) {
-            Ok(ext_bytes) => Some(get_blob_from_buf(ext_bytes)),
-            Err(_) => None,
+        .flat_map(|member_name| {
+            let member = PathBuf::from(format!(
+                "{}\u{2794}{}",
+                file.display(),
+                member_name
+            ));
+            match archive.extract(member_name, bytes) {
+                Ok(ext_bytes) => checksec_binaries(ext_bytes, &member),
+                Err(err) => vec![Binary::new(
+                    member,
+                    vec![Blob::error(&Error::Archive(format!(
+                        "Failed to extract archive member {member_name}: {err}"
+                    )))],
+                )],
+            }
         })
-        .flatten()
         .collect()
 }
-
-fn get_file_type(buffer: &[u8]) -> &str {
-    match infer::get(buffer) {
-        Some(file_kind) => file_kind.extension(),
-        _ => "Unknown",
-    }
-}
@@ -1,5 +1,5 @@
 //! Implements checksec for `MachO` binaries
-use crate::shared::{Endianness, Rpath, VecRpath};
+use crate::shared::{binary_dir_in_sysroot, Endianness, Rpath, VecRpath};
 #[cfg(feature = "color")]
 use colored::Colorize;
 use goblin::mach::constants::cputype::get_arch_name_from_types;
@@ -7,6 +7,7 @@ use goblin::mach::load_command::CommandVariant;
 use goblin::mach::MachO;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::Path;
 
 #[cfg(feature = "color")]
 use crate::colorize_bool;
@@ -72,6 +73,26 @@ pub struct CheckSecResults {
     pub symbol_count: usize,
 }
 impl CheckSecResults {
+    /// Check the @rpath directories of the binary at `binarypath` on disk
+    /// after expanding `@loader_path`, see `Rpath::check_dir`. Entries using
+    /// `@executable_path` are not checked since the main executable of a
+    /// library is unknown. Use `/` as `sysroot` for the host system.
+    pub fn check_search_dirs(&mut self, binarypath: &Path, sysroot: &Path) {
+        let loader_path = binary_dir_in_sysroot(binarypath, sysroot)
+            .and_then(|dir| dir.to_str().map(String::from));
+        self.rpath = self.rpath.check_dirs(sysroot, |entry| {
+            if entry.starts_with("@executable_path") {
+                return None;
+            }
+            match entry.strip_prefix("@loader_path") {
+                Some(rest) => {
+                    loader_path.as_ref().map(|dir| dir.clone() + rest)
+                }
+                None => Some(entry.into()),
+            }
+        });
+    }
+
     #[must_use]
     pub fn parse(macho: &MachO) -> Self {
         Self {
@@ -222,6 +243,8 @@ pub trait Properties {
     fn symbol_count(&self) -> usize;
     // return if the binary has asan instrumentation
     fn has_asan(&self) -> bool;
+    /// get the UUID from the `LC_UUID` load command
+    fn get_uuid(&self) -> Option<String>;
 }
 impl Properties for MachO<'_> {
     fn has_arc(&self) -> bool {
@@ -321,4 +344,21 @@ impl Properties for MachO<'_> {
         // check for asan initialization prologue. Apple adds an additional underscore in front of C symbols to differentiate from asm symbols.
         self.symbols().flatten().any(|(name, _)| name == "___asan_init")
     }
+    fn get_uuid(&self) -> Option<String> {
+        self.load_commands.iter().find_map(|loadcmd| {
+            if let CommandVariant::Uuid(cmd) = loadcmd.command {
+                let uuid = hex::encode_upper(cmd.uuid);
+                Some(format!(
+                    "{}-{}-{}-{}-{}",
+                    &uuid[..8],
+                    &uuid[8..12],
+                    &uuid[12..16],
+                    &uuid[16..20],
+                    &uuid[20..]
+                ))
+            } else {
+                None
+            }
+        })
+    }
 }
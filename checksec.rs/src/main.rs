@@ -8,22 +8,15 @@ extern crate ignore;
 extern crate serde_json;
 
 use clap::{
-    crate_authors, crate_description, crate_version, Arg, ArgAction, ArgGroup,
-    Command,
+    crate_authors, crate_description, crate_version, parser::ValueSource, Arg,
+    ArgAction, ArgGroup, ArgMatches, Command,
 };
-#[cfg(all(feature = "maps", target_os = "linux"))]
-use either::Either;
-use goblin::error::Error;
-#[cfg(feature = "macho")]
-use goblin::mach::{Mach, SingleArch::Archive, SingleArch::MachO};
-use goblin::Object;
 use ignore::Walk;
-#[cfg(all(feature = "maps", target_os = "linux"))]
-use itertools::Itertools;
 use memmap2::Mmap;
 use rayon::iter::ParallelBridge;
 use rayon::prelude::*;
 use serde_json::{json, to_string_pretty};
+#[cfg(not(target_os = "linux"))]
 use sysinfo::{ProcessRefreshKind, RefreshKind, System, UpdateKind};
 
 use std::collections::HashMap;
@@ -36,7 +29,8 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::{env, fmt, fs, process};
+use std::time::UNIX_EPOCH;
+use std::{env, fs, process};
 
 #[cfg(feature = "color")]
 use colored::{ColoredString, Colorize};
@@ -44,26 +38,29 @@ use colored::{ColoredString, Colorize};
 #[cfg(feature = "color")]
 use colored_json::to_colored_json_auto;
 
-mod binary;
-mod proc;
+use checksec::binary;
 
-use binary::{BinSpecificProperties, BinType, Binary, Blob};
-use proc::{Process, Processes};
+use binary::{BinSpecificProperties, Binary};
+#[cfg(target_os = "linux")]
+use checksec::proc::ProcFs;
+use checksec::proc::{
+    self, GroupBy, GroupedProcess, MappedFile, Process, ProcessGroups,
+    Processes, StaleImage,
+};
 
+use checksec::compare::Comparison;
+use checksec::diff::{BaselineReport, Diff};
 #[cfg(feature = "elf")]
 use checksec::elf;
 #[cfg(all(target_os = "linux", feature = "elf"))]
 use checksec::elf::LibraryLookup;
-#[cfg(all(target_os = "linux", feature = "elf"))]
-use checksec::ldso::LdSoError;
-#[cfg(feature = "macho")]
-use checksec::macho;
+use checksec::error::Error;
+use checksec::kernel::{KernelConfig, KernelReport, Sysctl};
 use checksec::output;
-#[cfg(feature = "pe")]
-use checksec::pe;
-#[cfg(all(target_os = "linux", feature = "elf"))]
-use checksec::shared::VecRpath;
-use checksec::{bold, underline};
+use checksec::policy::Policy;
+use checksec::sarif;
+use checksec::severity::SeverityProfile;
+use checksec::{bold, try_checksec_binaries, underline};
 
 #[cfg(all(feature = "color", target_os = "windows"))]
 fn print_filename(file: &Path) -> ColoredString {
@@ -105,8 +102,93 @@ fn print_filename(file: &Path) -> impl std::fmt::Display + '_ {
     file.display()
 }
 
+fn print_sarif_report(binaries: &[Binary], profile: &SeverityProfile) {
+    match sarif::get_sarif_report_with_profile(binaries, profile) {
+        Ok(report) => println!("{report}"),
+        Err(err) => {
+            eprintln!("Failed to generate SARIF report: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+fn print_comparison(
+    left: &Binary,
+    right: &Binary,
+    settings: &output::Settings,
+) {
+    let comparison = Comparison::new(left, right);
+    match settings.format {
+        output::Format::Sarif(ref profile) => {
+            match sarif::get_sarif_comparison(left, right, profile) {
+                Ok(report) => println!("{report}"),
+                Err(err) => {
+                    eprintln!("Failed to generate SARIF report: {err}");
+                    process::exit(1);
+                }
+            }
+        }
+        output::Format::Json => {
+            println!("{}", json!(comparison));
+        }
+        output::Format::JsonPretty => {
+            #[cfg(feature = "color")]
+            if settings.color {
+                if let Ok(colored_json) =
+                    to_colored_json_auto(&json!(comparison))
+                {
+                    println!("{colored_json}");
+                }
+            } else if let Ok(json_str) = to_string_pretty(&json!(comparison)) {
+                println!("{json_str}");
+            }
+            #[cfg(not(feature = "color"))]
+            if let Ok(json_str) = to_string_pretty(&json!(comparison)) {
+                println!("{json_str}");
+            }
+        }
+        output::Format::Text => print!("{comparison}"),
+    }
+}
+
+fn print_kernel_results(report: &KernelReport, settings: &output::Settings) {
+    match settings.format {
+        output::Format::Sarif(ref profile) => {
+            match sarif::get_sarif_kernel_report(report, profile) {
+                Ok(report) => println!("{report}"),
+                Err(err) => {
+                    eprintln!("Failed to generate SARIF report: {err}");
+                    process::exit(1);
+                }
+            }
+        }
+        output::Format::Json => {
+            println!("{}", json!(report));
+        }
+        output::Format::JsonPretty => {
+            #[cfg(feature = "color")]
+            if settings.color {
+                if let Ok(colored_json) = to_colored_json_auto(&json!(report))
+                {
+                    println!("{colored_json}");
+                }
+            } else if let Ok(json_str) = to_string_pretty(&json!(report)) {
+                println!("{json_str}");
+            }
+            #[cfg(not(feature = "color"))]
+            if let Ok(json_str) = to_string_pretty(&json!(report)) {
+                println!("{json_str}");
+            }
+        }
+        output::Format::Text => print!("{report}"),
+    }
+}
+
 fn print_binary_results(binaries: &[Binary], settings: &output::Settings) {
     match settings.format {
+        output::Format::Sarif(ref profile) => {
+            print_sarif_report(binaries, profile);
+        }
         output::Format::Json => {
             println!("{}", json!(binaries));
         }
@@ -162,8 +244,147 @@ fn print_binary_results(binaries: &[Binary], settings: &output::Settings) {
     }
 }
 
-fn print_process_results(processes: &Processes, settings: &output::Settings) {
+// Print the state of a process which differs between the processes of an
+// executable, labeled with its PID
+fn print_grouped_process(process: &GroupedProcess) {
+    #[cfg(target_os = "linux")]
+    if let Some(context) = &process.context {
+        println!(
+            " \u{21aa} {}({}) | {}",
+            bold!("Context"),
+            process.pid,
+            context
+        );
+    }
+    #[cfg(target_os = "linux")]
+    if let Some(runtime) = &process.runtime {
+        println!(
+            " \u{21aa} {}({}) | {}",
+            bold!("Runtime"),
+            process.pid,
+            runtime
+        );
+    }
+    for image in &process.stale {
+        println!(" \u{21aa} {}({}) | {}", bold!("Stale"), process.pid, image);
+    }
+    #[cfg(all(
+        feature = "maps",
+        any(target_os = "linux", target_os = "windows")
+    ))]
+    if let Some(summary) = &process.map_summary {
+        println!(
+            " \u{21aa} {}({}) | {}",
+            bold!("Mappings"),
+            process.pid,
+            summary
+        );
+    }
+}
+
+// Print groups as text or JSON, `--group-by` conflicts with `--sarif` as
+// SARIF results refer to files
+fn print_process_groups(groups: &ProcessGroups, settings: &output::Settings) {
     match settings.format {
+        output::Format::JsonPretty => {
+            #[cfg(feature = "color")]
+            if settings.color {
+                if let Ok(colored_json) = to_colored_json_auto(&json!(groups))
+                {
+                    println!("{colored_json}");
+                }
+            } else if let Ok(json_str) = to_string_pretty(&json!(groups)) {
+                println!("{json_str}");
+            }
+            #[cfg(not(feature = "color"))]
+            if let Ok(json_str) = to_string_pretty(&json!(groups)) {
+                println!("{json_str}");
+            }
+        }
+        output::Format::Text => {
+            let mut first = true;
+
+            for group in &groups.groups {
+                if !first {
+                    println!();
+                }
+                first = false;
+
+                let key =
+                    group.key.as_deref().unwrap_or(match groups.group_by {
+                        GroupBy::Container => "No container",
+                        GroupBy::Unit => "No unit",
+                        GroupBy::Executable => "n/a",
+                    });
+                println!(
+                    "{} ({} {})",
+                    underline!(bold!(key)),
+                    group.len(),
+                    if group.len() == 1 { "process" } else { "processes" }
+                );
+                for executable in &group.executables {
+                    for blob in &executable.binary.blobs {
+                        println!(
+                            "{}({})\n \u{21aa} {}: | {} | {} {}",
+                            bold!(executable
+                                .binary
+                                .file
+                                .file_name()
+                                .unwrap_or_else(|| OsStr::new("n/a"))
+                                .to_string_lossy()),
+                            executable
+                                .processes
+                                .iter()
+                                .map(|process| process.pid.to_string())
+                                .collect::<Vec<_>>()
+                                .join(","),
+                            blob.binarytype,
+                            blob.properties,
+                            underline!(bold!("File:")),
+                            print_filename(&executable.binary.file)
+                        );
+                    }
+                    for process in &executable.processes {
+                        print_grouped_process(process);
+                    }
+                    for library in executable.libraries.iter().flatten() {
+                        for blob in &library.blobs {
+                            println!(
+                                " \u{21aa} {}: | {} | {} {}",
+                                blob.binarytype,
+                                blob.properties,
+                                underline!(bold!("File:")),
+                                print_filename(&library.file)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        _ => println!("{}", json!(groups)),
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn print_process_results(
+    processes: &Processes,
+    settings: &output::Settings,
+    group_by: Option<GroupBy>,
+) {
+    if let Some(group_by) = group_by {
+        print_process_groups(&processes.grouped(group_by), settings);
+        return;
+    }
+    match settings.format {
+        output::Format::Sarif(ref profile) => {
+            match sarif::get_sarif_process_report(processes, profile) {
+                Ok(report) => println!("{report}"),
+                Err(err) => {
+                    eprintln!("Failed to generate SARIF report: {err}");
+                    process::exit(1);
+                }
+            }
+        }
         output::Format::Json => {
             println!("{}", json!(processes));
         }
@@ -208,6 +429,25 @@ fn print_process_results(processes: &Processes, settings: &output::Settings) {
                         print_filename(&process.binary.file)
                     );
                 }
+                #[cfg(target_os = "linux")]
+                if let Some(context) = &process.context {
+                    println!(" \u{21aa} {} | {}", bold!("Context"), context);
+                }
+                #[cfg(target_os = "linux")]
+                if let Some(runtime) = &process.runtime {
+                    println!(" \u{21aa} {} | {}", bold!("Runtime"), runtime);
+                }
+                #[cfg(target_os = "linux")]
+                for image in &process.stale {
+                    println!(" \u{21aa} {} | {}", bold!("Stale"), image);
+                }
+                #[cfg(all(
+                    feature = "maps",
+                    any(target_os = "linux", target_os = "windows")
+                ))]
+                if let Some(summary) = &process.map_summary {
+                    println!(" \u{21aa} {} | {}", bold!("Mappings"), summary);
+                }
 
                 #[cfg(all(
                     feature = "maps",
@@ -253,55 +493,31 @@ impl Lookup {
     fn elf_lookup(
         &self,
         binarypath: &Path,
-        rpath: &VecRpath,
-        runpath: &VecRpath,
+        elf: &elf::CheckSecResults,
         libfilename: &str,
     ) -> Option<PathBuf> {
-        self.elf.lookup(binarypath, rpath, runpath, libfilename)
+        self.elf.lookup(binarypath, elf, libfilename)
     }
-}
 
-enum ParseError {
-    Goblin(goblin::error::Error),
-    IO(std::io::Error),
     #[cfg(all(target_os = "linux", feature = "elf"))]
-    LdSo(LdSoError),
-    #[allow(dead_code)]
-    Unimplemented(&'static str),
-}
-
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Self::Goblin(e) => e.fmt(f),
-            Self::IO(e) => e.fmt(f),
-            #[cfg(all(target_os = "linux", feature = "elf"))]
-            Self::LdSo(e) => {
-                write!(f, "Failed to initialize library lookup: {e}")
-            }
-            Self::Unimplemented(str) => {
-                write!(f, "Support for files of type {str} not implemented")
-            }
-        }
-    }
-}
-
-impl From<goblin::error::Error> for ParseError {
-    fn from(err: goblin::error::Error) -> ParseError {
-        ParseError::Goblin(err)
+    fn elf_interpreter(&self, elf: &elf::CheckSecResults) -> Option<PathBuf> {
+        self.elf.interpreter(elf)
     }
-}
 
-impl From<std::io::Error> for ParseError {
-    fn from(err: std::io::Error) -> ParseError {
-        ParseError::IO(err)
+    #[cfg(all(target_os = "linux", feature = "elf"))]
+    fn new(sysroot: Option<&Path>) -> Result<Self, Error> {
+        Ok(Self {
+            elf: match sysroot {
+                Some(sysroot) => LibraryLookup::with_sysroot(sysroot)?,
+                None => LibraryLookup::new()?,
+            },
+        })
     }
-}
 
-#[cfg(all(target_os = "linux", feature = "elf"))]
-impl From<LdSoError> for ParseError {
-    fn from(err: LdSoError) -> ParseError {
-        ParseError::LdSo(err)
+    #[cfg(not(all(target_os = "linux", feature = "elf")))]
+    #[allow(clippy::unnecessary_wraps)]
+    fn new(_sysroot: Option<&Path>) -> Result<Self, Error> {
+        Ok(Self {})
     }
 }
 
@@ -310,7 +526,7 @@ type Cache = Arc<Mutex<HashMap<PathBuf, Vec<Binary>>>>;
 fn parse(
     file: &Path,
     cache: &mut Option<Cache>,
-) -> Result<Vec<Binary>, ParseError> {
+) -> Result<Vec<Binary>, Error> {
     if let Some(ref mut cache) = cache {
         let cache = cache.lock().unwrap();
         if let Some(entry) = cache.get(file) {
@@ -321,7 +537,17 @@ fn parse(
     let fp = fs::File::open(file)?;
     let buffer = unsafe { Mmap::map(&fp)? };
 
-    let result = parse_bytes(&buffer, file)?;
+    let mtime = fp
+        .metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|age| age.as_secs());
+    let mut result = try_checksec_binaries(&buffer, file)?;
+    // archive members are named after the archive and its member
+    for binary in result.iter_mut().filter(|binary| binary.file == file) {
+        binary.mtime = mtime;
+    }
     if let Some(ref mut cache) = cache {
         let mut cache = cache.lock().unwrap();
         cache.insert(file.to_path_buf(), result.clone());
@@ -330,141 +556,12 @@ fn parse(
     Ok(result)
 }
 
-#[allow(clippy::too_many_lines)]
-fn parse_bytes(bytes: &[u8], file: &Path) -> Result<Vec<Binary>, ParseError> {
-    match Object::parse(bytes)? {
-        #[cfg(feature = "elf")]
-        Object::Elf(elf) => {
-            let results = elf::CheckSecResults::parse(&elf, bytes);
-            let bin_type =
-                if elf.is_64 { BinType::Elf64 } else { BinType::Elf32 };
-            Ok(vec![Binary::new(
-                file.to_path_buf(),
-                vec![Blob::new(bin_type, BinSpecificProperties::Elf(results))],
-            )])
-        }
-        #[cfg(feature = "pe")]
-        Object::PE(pe) => {
-            let results = pe::CheckSecResults::parse(&pe, bytes);
-            let bin_type =
-                if pe.is_64 { BinType::PE64 } else { BinType::PE32 };
-            Ok(vec![Binary::new(
-                file.to_path_buf(),
-                vec![Blob::new(bin_type, BinSpecificProperties::PE(results))],
-            )])
-        }
-        #[cfg(feature = "macho")]
-        Object::Mach(mach) => {
-            match mach {
-                Mach::Binary(macho) => {
-                    let results = macho::CheckSecResults::parse(&macho);
-                    let bin_type = if macho.is_64 {
-                        BinType::MachO64
-                    } else {
-                        BinType::MachO32
-                    };
-                    Ok(vec![Binary::new(
-                        file.to_path_buf(),
-                        vec![Blob::new(
-                            bin_type,
-                            BinSpecificProperties::MachO(results),
-                        )],
-                    )])
-                }
-                Mach::Fat(fatmach) => {
-                    let mut fat_bins: Vec<Binary> = Vec::new();
-                    let mut fat_blobs: Vec<Blob> = Vec::new();
-                    for (idx, fatarch) in fatmach.iter_arches().enumerate() {
-                        if let Ok(container) = fatmach.get(idx) {
-                            match container {
-                                MachO(mach) => {
-                                    let results =
-                                        macho::CheckSecResults::parse(&mach);
-                                    let bin_type = if mach.is_64 {
-                                        BinType::MachO64
-                                    } else {
-                                        BinType::MachO32
-                                    };
-                                    fat_blobs.push(Blob::new(
-                                        bin_type,
-                                        BinSpecificProperties::MachO(results),
-                                    ));
-                                }
-                                Archive(archive) => {
-                                    let fatarch = fatarch?;
-                                    if let Some(archive_bytes) = bytes.get(
-                                        fatarch.offset as usize
-                                            ..(fatarch.offset + fatarch.size)
-                                                as usize,
-                                    ) {
-                                        fat_bins.append(&mut parse_archive(
-                                            &archive,
-                                            file,
-                                            archive_bytes,
-                                        ));
-                                    } else {
-                                        Err(goblin::error::Error::Malformed("Archive refers to invalid position".to_string()))?;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    fat_bins.push(Binary::new(file.to_path_buf(), fat_blobs));
-                    Ok(fat_bins)
-                }
-            }
-        }
-        #[cfg(not(feature = "elf"))]
-        Object::Elf(_) => Err(ParseError::Unimplemented("ELF")),
-        #[cfg(not(feature = "pe"))]
-        Object::PE(_) => Err(ParseError::Unimplemented("PE")),
-        #[cfg(not(feature = "macho"))]
-        Object::Mach(_) => Err(ParseError::Unimplemented("MachO")),
-        Object::Archive(archive) => Ok(parse_archive(&archive, file, bytes)),
-        Object::Unknown(magic) => {
-            Err(ParseError::Goblin(Error::BadMagic(magic)))
-        }
-        _ => Err(ParseError::Unimplemented("Unkown file provided")),
-    }
-}
-
-fn parse_archive(
-    archive: &goblin::archive::Archive,
-    file: &Path,
-    bytes: &[u8],
-) -> Vec<Binary> {
-    archive
-        .members()
-        .iter()
-        .filter_map(|member_name| match archive.extract(member_name, bytes) {
-            Ok(ext_bytes) => parse_bytes(
-                ext_bytes,
-                Path::new(&format!(
-                    "{}\u{2794}{}",
-                    file.display(),
-                    member_name
-                )),
-            )
-            .ok(),
-            Err(err) => {
-                eprintln!(
-                    "Failed to extract member {} of {}: {}",
-                    member_name,
-                    file.display(),
-                    err
-                );
-                None
-            }
-        })
-        .flatten()
-        .collect()
-}
-
 #[cfg(not(all(target_os = "linux", feature = "elf")))]
 fn parse_single_file(
     file: &Path,
     _scan_dynlibs: bool,
-) -> Result<Vec<Binary>, ParseError> {
+    _sysroot: Option<&Path>,
+) -> Result<Vec<Binary>, Error> {
     parse(file, &mut None)
 }
 
@@ -474,10 +571,19 @@ fn parse_file_impl(
     _scan_dynlibs: bool,
     _lookup: Option<&Lookup>,
     cache: &mut Option<Cache>,
-) -> Result<Vec<Binary>, ParseError> {
+) -> Result<Vec<Binary>, Error> {
     parse(file, cache)
 }
 
+// Libraries are often reached through different symlinks, e.g. the
+// interpreter and the DT_NEEDED entry of libc for ld.so
+#[cfg(all(target_os = "linux", feature = "elf"))]
+fn is_scanned(scanned: &HashSet<PathBuf>, path: &Path) -> bool {
+    scanned.contains(path)
+        || std::fs::canonicalize(path)
+            .is_ok_and(|canonical| scanned.contains(&canonical))
+}
+
 #[cfg(all(target_os = "linux", feature = "elf"))]
 fn scan_dependencies(
     binary: &Binary,
@@ -489,15 +595,15 @@ fn scan_dependencies(
     for blob in &binary.blobs {
         #[allow(irrefutable_let_patterns)]
         if let BinSpecificProperties::Elf(elf_properties) = &blob.properties {
+            if let Some(path) = lookup.elf_interpreter(elf_properties) {
+                if !is_scanned(scanned, &path) {
+                    to_scan.insert(path);
+                }
+            }
             for dynlib in &elf_properties.dynlibs {
-                match lookup.elf_lookup(
-                    &binary.file,
-                    &elf_properties.rpath,
-                    &elf_properties.runpath,
-                    dynlib,
-                ) {
+                match lookup.elf_lookup(&binary.file, elf_properties, dynlib) {
                     Some(path) => {
-                        if scanned.contains(&path) {
+                        if is_scanned(scanned, &path) {
                             continue;
                         }
                         to_scan.insert(path);
@@ -544,6 +650,9 @@ fn parse_dependencies(
             .flatten()
             .collect();
 
+        scanned.extend(
+            to_scan.iter().filter_map(|path| std::fs::canonicalize(path).ok()),
+        );
         scanned.extend(to_scan);
 
         to_scan = results
@@ -561,7 +670,7 @@ fn parse_file_impl(
     scan_dynlibs: bool,
     lookup: Option<&Lookup>,
     cache: &mut Option<Cache>,
-) -> Result<Vec<Binary>, ParseError> {
+) -> Result<Vec<Binary>, Error> {
     let mut results = parse(file, cache)?;
 
     if !scan_dynlibs || lookup.is_none() {
@@ -581,36 +690,42 @@ fn parse_file_impl(
 fn parse_single_file(
     file: &Path,
     scan_dynlibs: bool,
-) -> Result<Vec<Binary>, ParseError> {
+    sysroot: Option<&Path>,
+) -> Result<Vec<Binary>, Error> {
     if !scan_dynlibs {
         return parse(file, &mut None);
     }
 
-    let lookup = Lookup { elf: LibraryLookup::new()? };
+    let lookup = Lookup::new(sysroot)?;
 
     parse_file_impl(file, true, Some(&lookup), &mut None)
 }
 
+// Mark writable, missing and relative runtime search path directories
+fn check_search_dirs(bins: &mut [Binary], sysroot: Option<&Path>) {
+    for bin in bins {
+        bin.check_search_dirs(sysroot.unwrap_or(Path::new("/")));
+    }
+}
+
 fn walk(
     basepath: &Path,
     scan_dynlibs: bool,
-    output_settings: &output::Settings,
-) {
+    sysroot: Option<&Path>,
+) -> Vec<Binary> {
     let lookup = if scan_dynlibs {
-        Some(&Lookup {
-            #[cfg(all(target_os = "linux", feature = "elf"))]
-            elf: LibraryLookup::new().unwrap_or_else(|err| {
-                eprintln!("Failed to initialize library lookup: {err}");
-                process::exit(1)
-            }),
-        })
+        Some(Lookup::new(sysroot).unwrap_or_else(|err| {
+            eprintln!("Failed to initialize library lookup: {err}");
+            process::exit(1)
+        }))
     } else {
         None
     };
+    let lookup = lookup.as_ref();
 
     let cache = Arc::new(Mutex::new(HashMap::new()));
 
-    let bins: Vec<Binary> = Walk::new(basepath)
+    let mut bins: Vec<Binary> = Walk::new(basepath)
         .flatten()
         .filter(|entry| {
             entry.file_type().as_ref().is_some_and(std::fs::FileType::is_file)
@@ -628,96 +743,431 @@ fn walk(
         .flatten()
         .collect();
 
-    print_binary_results(&bins, output_settings);
+    check_search_dirs(&mut bins, sysroot);
+    bins
 }
 
-#[cfg(all(feature = "maps", target_os = "linux"))]
-fn parse_process_libraries(
-    process: &sysinfo::Process,
-    cache: &mut Option<Cache>,
-) -> Result<Vec<Binary>, std::io::Error> {
-    Ok(Process::parse_maps(process.pid().as_u32() as usize)?
-        .into_iter()
-        .filter(|m| m.flags.x)
-        .filter_map(|m| m.pathname)
-        .filter(|p| {
-            p.is_absolute() && process.exe().is_none_or(|exe| p != exe)
+// Print all policy violations and exit with an error if there are any
+fn enforce_policy<'a, I>(policy: Option<&Policy>, bins: I)
+where
+    I: IntoIterator<Item = &'a Binary>,
+{
+    let Some(policy) = policy else {
+        return;
+    };
+    let mut violated = false;
+    for violation in bins.into_iter().flat_map(|bin| policy.check_binary(bin))
+    {
+        eprintln!("Policy violation: {violation}");
+        violated = true;
+    }
+    if violated {
+        process::exit(1);
+    }
+}
+
+// Run checksec on both files of the compare subcommand and print the
+// differing properties
+fn compare(args: &ArgMatches) {
+    let binaries: Vec<Binary> = ["left", "right"]
+        .iter()
+        .filter_map(|arg| args.get_one::<String>(arg))
+        .map(|file| {
+            let buffer = fs::read(file).unwrap_or_else(|err| {
+                eprintln!("Cannot read file {}: {}", underline!(file), err);
+                process::exit(1);
+            });
+            checksec::checksec(&buffer, file.clone())
         })
-        .map(|p| match p.file_name() {
-            Some(file_name) => match file_name.to_str() {
-                Some(file_name) => {
-                    match file_name.strip_suffix(" (deleted)") {
-                        Some(s) => {
-                            let mut pb = PathBuf::from(
-                                p.parent().unwrap_or(Path::new("/")),
+        .collect();
+    let [left, right] = binaries.as_slice() else {
+        unreachable!("compare requires two files");
+    };
+    let settings = output::Settings::set(
+        #[cfg(feature = "color")]
+        !args.get_flag("no-color"),
+        output_format(args),
+        false,
+        false,
+    );
+    print_comparison(left, right, &settings);
+}
+
+fn output_format(args: &ArgMatches) -> output::Format {
+    if args.get_flag("sarif") {
+        output::Format::Sarif(args.get_one::<String>("severity").map_or_else(
+            SeverityProfile::default,
+            |profile| {
+                SeverityProfile::builtin(profile).unwrap_or_else(|| {
+                    SeverityProfile::from_file(Path::new(profile))
+                        .unwrap_or_else(|err| {
+                            eprintln!(
+                                "Cannot load severity profile {}: {}",
+                                underline!(profile),
+                                err
                             );
-                            pb.push(s);
-                            Either::Left(pb)
-                        }
-                        None => Either::Right(p),
-                    }
-                }
-                None => Either::Right(p),
+                            process::exit(1);
+                        })
+                })
             },
-            None => Either::Right(p),
+        ))
+    } else if args.get_flag("json") {
+        if args.get_flag("pretty") {
+            output::Format::JsonPretty
+        } else {
+            output::Format::Json
+        }
+    } else {
+        output::Format::Text
+    }
+}
+
+// Check the kernel configuration and the optional sysctl snapshot of the
+// kernel mode
+fn kernel(config: &str, sysctl: Option<&String>) -> KernelReport {
+    let config =
+        KernelConfig::from_file(Path::new(config)).unwrap_or_else(|err| {
+            eprintln!(
+                "Cannot read kernel configuration {}: {}",
+                underline!(config),
+                err
+            );
+            process::exit(1);
+        });
+    let sysctl = sysctl.map(|sysctl| {
+        Sysctl::from_path(Path::new(sysctl)).unwrap_or_else(|err| {
+            eprintln!(
+                "Cannot read sysctl settings {}: {}",
+                underline!(sysctl),
+                err
+            );
+            process::exit(1);
         })
-        .unique()
-        .par_bridge()
-        .filter_map(|p| {
-            parse(&p, &mut cache.as_ref().map(Arc::clone))
-                .map_err(|err| {
-                    if let ParseError::IO(ref e) = err {
-                        if e.kind() == ErrorKind::NotFound
-                            || e.kind() == ErrorKind::PermissionDenied
-                        {
-                            return;
-                        }
-                    }
+    });
+    KernelReport::new(&config, sysctl.as_ref())
+}
 
-                    eprintln!(
-                        "Failed to parse '{}' for process ID {}: {}",
-                        p.display(),
-                        process.pid(),
-                        err
-                    );
+// Print the results of a scan with its differences to a baseline scan and
+// return whether any mitigation was lost
+fn print_scan_results(
+    bins: &[Binary],
+    baseline: Option<&[Binary]>,
+    settings: &output::Settings,
+) -> bool {
+    let Some(baseline) = baseline else {
+        print_binary_results(bins, settings);
+        return false;
+    };
+    let diff = Diff::new(baseline, bins);
+    let regressed = diff.has_regressions();
+    match settings.format {
+        output::Format::Sarif(ref profile) => {
+            match sarif::get_sarif_baseline_report(baseline, bins, profile) {
+                Ok(report) => println!("{report}"),
+                Err(err) => {
+                    eprintln!("Failed to generate SARIF report: {err}");
+                    process::exit(1);
+                }
+            }
+        }
+        output::Format::Json => {
+            let report = BaselineReport { binaries: bins.to_vec(), diff };
+            println!("{}", json!(report));
+        }
+        output::Format::JsonPretty => {
+            let report = BaselineReport { binaries: bins.to_vec(), diff };
+            #[cfg(feature = "color")]
+            if settings.color {
+                if let Ok(colored_json) = to_colored_json_auto(&json!(report))
+                {
+                    println!("{colored_json}");
+                }
+            } else if let Ok(json_str) = to_string_pretty(&json!(report)) {
+                println!("{json_str}");
+            }
+            #[cfg(not(feature = "color"))]
+            if let Ok(json_str) = to_string_pretty(&json!(report)) {
+                println!("{json_str}");
+            }
+        }
+        output::Format::Text => {
+            print_binary_results(bins, settings);
+            eprint!("{diff}");
+        }
+    }
+    regressed
+}
+
+fn process_binaries(process: &Process) -> impl Iterator<Item = &Binary> {
+    std::iter::once(&process.binary).chain(process.libraries.iter().flatten())
+}
+
+// A running process to analyze
+struct ProcessInfo {
+    pid: usize,
+    name: String,
+    exe: Option<PathBuf>,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl From<&sysinfo::Process> for ProcessInfo {
+    fn from(process: &sysinfo::Process) -> Self {
+        Self {
+            pid: process.pid().as_u32() as usize,
+            name: process.name().to_string_lossy().to_string(),
+            exe: process.exe().map(Path::to_path_buf),
+        }
+    }
+}
+
+// Where running processes and their files are read from, a procfs tree on
+// Linux
+struct ProcessSource {
+    #[cfg(target_os = "linux")]
+    procfs: ProcFs,
+}
+
+impl ProcessSource {
+    #[cfg(target_os = "linux")]
+    fn processes(&self) -> Vec<ProcessInfo> {
+        match self.procfs.pids() {
+            Ok(pids) => pids
+                .into_iter()
+                .map(|pid| ProcessInfo {
+                    pid,
+                    name: self.procfs.name(pid).unwrap_or_default(),
+                    exe: self.procfs.exe(pid),
                 })
-                .ok()
+                .collect(),
+            Err(err) => {
+                eprintln!(
+                    "Cannot read processes from {}: {}",
+                    underline!(self.procfs.root.display().to_string()),
+                    err
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn processes(&self) -> Vec<ProcessInfo> {
+        let refresh_kind = RefreshKind::nothing().with_processes(
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_exe(UpdateKind::Always),
+        );
+        System::new_with_specifics(refresh_kind)
+            .processes()
+            .values()
+            .map(ProcessInfo::from)
+            .collect()
+    }
+
+    // Path to read the executable of a process from
+    #[cfg(target_os = "linux")]
+    fn resolve(
+        &self,
+        process: &ProcessInfo,
+        exe: &Path,
+    ) -> std::io::Result<PathBuf> {
+        self.procfs.resolve(process.pid, exe)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    fn resolve(
+        &self,
+        _process: &ProcessInfo,
+        exe: &Path,
+    ) -> std::io::Result<PathBuf> {
+        Ok(exe.to_path_buf())
+    }
+
+    // Root directory to check the search paths of a process in
+    #[cfg(target_os = "linux")]
+    fn sysroot(&self, process: &ProcessInfo) -> std::io::Result<PathBuf> {
+        self.procfs.sysroot(process.pid)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    fn sysroot(&self, _process: &ProcessInfo) -> std::io::Result<PathBuf> {
+        Ok(PathBuf::from("/"))
+    }
+
+    // The executable of a process and where to read its running image from
+    #[cfg(target_os = "linux")]
+    fn image(&self, process: &ProcessInfo, exe: &Path) -> MappedFile {
+        // the running image if the file can not be found at its path
+        self.procfs.executable(process.pid).unwrap_or_else(|| MappedFile {
+            file: exe.to_path_buf(),
+            source: self
+                .resolve(process, exe)
+                .unwrap_or_else(|_| self.procfs.path(process.pid, "exe")),
+            stale: false,
         })
-        .flatten()
-        .collect::<Vec<Binary>>())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[allow(clippy::unused_self)]
+    fn image(&self, _process: &ProcessInfo, exe: &Path) -> MappedFile {
+        MappedFile {
+            file: exe.to_path_buf(),
+            source: exe.to_path_buf(),
+            stale: false,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn process(
+        &self,
+        pid: usize,
+        binary: Binary,
+        libraries: Option<Vec<Binary>>,
+    ) -> Process {
+        self.procfs.process(pid, binary, libraries)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[allow(clippy::unused_self)]
+    fn process(
+        &self,
+        pid: usize,
+        binary: Binary,
+        libraries: Option<Vec<Binary>>,
+    ) -> Process {
+        Process::new(pid, binary, libraries)
+    }
+}
+
+// Parse `source` and report its binaries as `file`, e.g. for files of a
+// process read through its procfs entries
+fn parse_as(
+    source: &Path,
+    file: &Path,
+    cache: &mut Option<Cache>,
+) -> Result<Vec<Binary>, Error> {
+    let mut binaries = parse(source, cache)?;
+    for binary in binaries.iter_mut().filter(|binary| binary.file == source) {
+        binary.file = file.to_path_buf();
+    }
+    Ok(binaries)
+}
+
+// Compare the running image of a deleted or replaced file of a process with
+// the file now at its path
+fn stale_image(
+    image: &MappedFile,
+    running: Option<&Binary>,
+    process: &ProcessInfo,
+    source: &ProcessSource,
+    cache: &mut Option<Cache>,
+) -> StaleImage {
+    let replacement = source
+        .resolve(process, &image.file)
+        .ok()
+        .and_then(|path| parse_as(&path, &image.file, cache).ok());
+    StaleImage::new(
+        image,
+        running,
+        replacement.as_ref().and_then(|binaries| binaries.first()),
+    )
+}
+
+#[cfg(all(feature = "maps", target_os = "linux"))]
+fn parse_process_libraries(
+    process: &ProcessInfo,
+    source: &ProcessSource,
+    cache: &mut Option<Cache>,
+) -> Result<(Vec<Binary>, Vec<StaleImage>), std::io::Error> {
+    let maps = source.procfs.parse_maps(process.pid)?;
+    let (libraries, stale): (Vec<Vec<Binary>>, Vec<Option<StaleImage>>) =
+        source
+            .procfs
+            .libraries(process.pid, &maps, process.exe.as_deref())
+            .into_par_iter()
+            .map(|library| {
+                let binaries =
+                    parse_library(&library, process, cache.as_ref());
+                let stale = library.stale.then(|| {
+                    stale_image(
+                        &library,
+                        binaries.first(),
+                        process,
+                        source,
+                        &mut cache.as_ref().map(Arc::clone),
+                    )
+                });
+                (binaries, stale)
+            })
+            .unzip();
+    Ok((libraries.concat(), stale.into_iter().flatten().collect()))
+}
+
+#[cfg(all(feature = "maps", target_os = "linux"))]
+fn parse_library(
+    library: &MappedFile,
+    process: &ProcessInfo,
+    cache: Option<&Cache>,
+) -> Vec<Binary> {
+    parse_as(&library.source, &library.file, &mut cache.map(Arc::clone))
+        .map_err(|err| {
+            if let Error::IO(ref e) = err {
+                if e.kind() == ErrorKind::NotFound
+                    || e.kind() == ErrorKind::PermissionDenied
+                {
+                    return;
+                }
+            }
+
+            eprintln!(
+                "Failed to parse '{}' for process ID {}: {}",
+                library.file.display(),
+                process.pid,
+                err
+            );
+        })
+        .unwrap_or_default()
 }
 
 #[cfg(not(all(feature = "maps", target_os = "linux")))]
 fn parse_process_libraries(
-    _process: &sysinfo::Process,
+    _process: &ProcessInfo,
+    _source: &ProcessSource,
     _cache: &mut Option<Cache>,
-) -> Result<Vec<Binary>, std::io::Error> {
+) -> Result<(Vec<Binary>, Vec<StaleImage>), std::io::Error> {
     Err(std::io::Error::new(
         ErrorKind::Unsupported,
         "parse_process_libraries()",
     ))
 }
 
-fn parse_processes<'a, I>(processes: I, scan_dynlibs: bool) -> Vec<Process>
-where
-    I: Iterator<Item = &'a sysinfo::Process> + Send,
-{
+fn parse_processes(
+    processes: Vec<ProcessInfo>,
+    scan_dynlibs: bool,
+    source: &ProcessSource,
+) -> Vec<Process> {
     let cache = Arc::new(Mutex::new(HashMap::new()));
 
     processes
-        .par_bridge()
+        .into_par_iter()
         .filter_map(|process| {
-            let Some(exe) = process.exe() else {
+            let Some(exe) = &process.exe else {
                 eprintln!(
                     "No executable path for process {} with ID {}",
-                    process.name().to_string_lossy(),
-                    process.pid()
+                    process.name, process.pid
                 );
                 return None;
             };
-            match parse(exe, &mut Some(Arc::clone(&cache))) {
+            let image = source.image(&process, exe);
+            match parse_as(
+                &image.source,
+                &image.file,
+                &mut Some(Arc::clone(&cache)),
+            ) {
                 Err(err) => {
-                    if let ParseError::IO(ref e) = err {
+                    if let Error::IO(ref e) = err {
                         if e.kind() == ErrorKind::NotFound
                             || e.kind() == ErrorKind::PermissionDenied
                         {
@@ -727,32 +1177,65 @@ where
 
                     eprintln!(
                         "Can not parse process {} with ID {}: {}",
-                        process.name().to_string_lossy(),
-                        process.pid(),
-                        err
+                        process.name, process.pid, err
                     );
 
                     None
                 }
-                Ok(bins) => Some(
-                    bins.into_iter()
-                        .map(|bin| {
-                            Process::new(
-                                process.pid().as_u32() as usize,
-                                bin,
-                                if scan_dynlibs {
-                                    parse_process_libraries(
-                                        process,
-                                        &mut Some(Arc::clone(&cache)),
-                                    )
-                                    .ok()
-                                } else {
-                                    None
-                                },
-                            )
-                        })
-                        .collect::<Vec<proc::Process>>(),
-                ),
+                Ok(mut bins) => {
+                    // never check the search paths of other roots on the host
+                    let sysroot = source.sysroot(&process).ok();
+                    if let Some(sysroot) = &sysroot {
+                        check_search_dirs(&mut bins, Some(sysroot));
+                    }
+                    let (libraries, mut stale) = if scan_dynlibs {
+                        parse_process_libraries(
+                            &process,
+                            source,
+                            &mut Some(Arc::clone(&cache)),
+                        )
+                        .ok()
+                        .map_or(
+                            (None, Vec::new()),
+                            |(mut libs, stale)| {
+                                if let Some(sysroot) = &sysroot {
+                                    check_search_dirs(
+                                        &mut libs,
+                                        Some(sysroot),
+                                    );
+                                }
+                                (Some(libs), stale)
+                            },
+                        )
+                    } else {
+                        (None, Vec::new())
+                    };
+                    if image.stale {
+                        stale.insert(
+                            0,
+                            stale_image(
+                                &image,
+                                bins.first(),
+                                &process,
+                                source,
+                                &mut Some(Arc::clone(&cache)),
+                            ),
+                        );
+                    }
+                    Some(
+                        bins.into_iter()
+                            .map(|bin| {
+                                let mut process = source.process(
+                                    process.pid,
+                                    bin,
+                                    libraries.clone(),
+                                );
+                                process.stale.clone_from(&stale);
+                                process
+                            })
+                            .collect::<Vec<proc::Process>>(),
+                    )
+                }
             }
         })
         .flatten()
@@ -761,11 +1244,26 @@ where
 
 #[allow(clippy::too_many_lines, clippy::cognitive_complexity)]
 fn main() {
-    let args = Command::new("checksec")
+    let command = Command::new("checksec")
         .about(crate_description!())
         .author(crate_authors!())
         .version(crate_version!())
         .arg_required_else_help(true)
+        .arg(
+            Arg::new("baseline")
+                .long("baseline")
+                .value_name("REPORT")
+                .help(
+                    "Compare results with a JSON report of a previous scan\n\
+                    (exits with an error if mitigations were lost)",
+                )
+                .conflicts_with_all([
+                    "pid",
+                    "process",
+                    "process-all",
+                    "kernel",
+                ]),
+        )
         .arg(
             Arg::new("directory")
                 .short('d')
@@ -785,7 +1283,21 @@ fn main() {
                 .short('j')
                 .long("json")
                 .action(ArgAction::SetTrue)
-                .help("Output in json format"),
+                .help("Output in json format")
+                .global(true),
+        )
+        .arg(
+            Arg::new("kernel")
+                .short('k')
+                .long("kernel")
+                .value_name("CONFIG")
+                .num_args(0..=1)
+                .default_missing_value("/proc/config.gz")
+                .help(
+                    "Check the hardening options of a kernel configuration\n\
+                    (default: /proc/config.gz of the running kernel)",
+                )
+                .conflicts_with("policy"),
         )
         .arg(
             Arg::new("libraries")
@@ -810,11 +1322,33 @@ fn main() {
                 .requires("process-all")
                 .conflicts_with_all(["directory", "file"]),
         )
+        .arg(
+            Arg::new("procfs")
+                .long("procfs")
+                .value_name("DIR")
+                .help(
+                    "Read processes from another procfs tree, e.g. of a \
+                    container host or a captured snapshot (Linux only)",
+                )
+                .conflicts_with_all(["directory", "file", "kernel"]),
+        )
+        .arg(
+            Arg::new("group-by")
+                .long("group-by")
+                .value_name("KEY")
+                .value_parser(["container", "unit", "executable"])
+                .help(
+                    "Group processes by container, systemd unit or \
+                    executable in text and json output",
+                )
+                .conflicts_with_all(["directory", "file", "kernel", "sarif"]),
+        )
         .arg(
             Arg::new("no-color")
                 .long("no-color")
                 .action(ArgAction::SetTrue)
-                .help("Disables color output"),
+                .help("Disables color output")
+                .global(true),
         )
         .arg(
             Arg::new("pid")
@@ -825,12 +1359,17 @@ fn main() {
                 .long("pid")
                 .value_name("PID"),
         )
+        .arg(Arg::new("policy").long("policy").value_name("FILE").help(
+            "Check results against a TOML/JSON policy of required \
+                    mitigations\n(exits with an error on violations)",
+        ))
         .arg(
             Arg::new("pretty")
                 .long("pretty")
                 .action(ArgAction::SetTrue)
                 .help("Human readable json output")
-                .requires("json"),
+                .requires("json")
+                .global(true),
         )
         .arg(
             Arg::new("process")
@@ -846,12 +1385,116 @@ fn main() {
                 .action(ArgAction::SetTrue)
                 .help("Check all running processes"),
         )
+        .arg(
+            Arg::new("sarif")
+                .long("sarif")
+                .action(ArgAction::SetTrue)
+                .help("Output in SARIF format")
+                .conflicts_with("json")
+                .global(true),
+        )
+        .arg(
+            Arg::new("severity")
+                .long("severity")
+                .value_name("PROFILE")
+                .help(
+                    "Severity of failed checks in SARIF output: default, \
+                    strict,\ninformational or a TOML/JSON mapping file",
+                )
+                .requires("sarif")
+                .global(true),
+        )
+        .arg(
+            Arg::new("sysctl")
+                .long("sysctl")
+                .value_name("FILE")
+                .help(
+                    "Check sysctl settings of a `sysctl -a` snapshot or a \
+                    /proc/sys tree\n(kernel mode only)",
+                )
+                .requires("kernel"),
+        )
+        .arg(
+            Arg::new("sysroot")
+                .long("sysroot")
+                .value_name("DIR")
+                .help("Root directory for library lookups (Linux only)")
+                .requires("libraries")
+                .conflicts_with_all(["pid", "process", "process-all"]),
+        )
         .group(
             ArgGroup::new("operation")
-                .args(["directory", "file", "pid", "process", "process-all"])
+                .args([
+                    "directory",
+                    "file",
+                    "kernel",
+                    "pid",
+                    "process",
+                    "process-all",
+                ])
                 .required(true),
         )
-        .get_matches();
+        .subcommand(
+            Command::new("compare")
+                .about("Compare the properties of two binaries")
+                .arg(
+                    Arg::new("left")
+                        .value_name("A")
+                        .required(true)
+                        .help("First binary, e.g. a debug build"),
+                )
+                .arg(
+                    Arg::new("right")
+                        .value_name("B")
+                        .required(true)
+                        .help("Second binary, e.g. a release build"),
+                ),
+        )
+        .subcommand_negates_reqs(true);
+    // memory mappings are only read with the maps feature
+    #[cfg(all(
+        feature = "maps",
+        any(target_os = "linux", target_os = "windows")
+    ))]
+    let command = command.arg(
+        Arg::new("unsafe-maps")
+            .long("unsafe-maps")
+            .action(ArgAction::SetTrue)
+            .help(
+                "Only include processes with writable and executable, \
+                anonymous executable or executable stack/heap mappings \
+                (Linux and Windows only)",
+            )
+            .requires("process-all"),
+    );
+    let mut command = command;
+    let args = command.get_matches_mut();
+
+    if let Some(("compare", compare_args)) = args.subcommand() {
+        // only the global output options apply to the compared binaries
+        let scan_arg = command
+            .get_arguments()
+            .find(|arg| {
+                !arg.is_global_set()
+                    && args.value_source(arg.get_id().as_str())
+                        == Some(ValueSource::CommandLine)
+            })
+            .and_then(Arg::get_long)
+            .map(str::to_string);
+        if let Some(long) = scan_arg {
+            command
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    format!(
+                        "the subcommand 'compare' cannot be used with \
+                        '--{long}'"
+                    ),
+                )
+                .exit();
+        }
+        compare(compare_args);
+        return;
+    }
 
     // required operation
     let file = args.get_one::<String>("file");
@@ -859,42 +1502,101 @@ fn main() {
     let procids = args.get_one::<String>("pid");
     let procname = args.get_one::<String>("process");
     let procall = args.get_flag("process-all");
+    let kernel_config = args.get_one::<String>("kernel");
 
     // optional modifiers
     let libraries = args.get_flag("libraries");
-
-    let format = if args.get_flag("json") {
-        if args.get_flag("pretty") {
-            output::Format::JsonPretty
-        } else {
-            output::Format::Json
+    let sysroot = args.get_one::<String>("sysroot");
+    if let Some(sysroot) = sysroot {
+        if !Path::new(sysroot).is_dir() {
+            eprintln!("Sysroot {} not found", underline!(sysroot));
+            process::exit(1);
         }
-    } else {
-        output::Format::Text
-    };
+    }
+    let sysroot = sysroot.map(Path::new);
+    let policy = args.get_one::<String>("policy").map(|policy| {
+        Policy::from_file(Path::new(policy)).unwrap_or_else(|err| {
+            eprintln!("Cannot load policy {}: {}", underline!(policy), err);
+            process::exit(1);
+        })
+    });
+    let baseline = args.get_one::<String>("baseline").map(|baseline| {
+        fs::read_to_string(baseline)
+            .map_err(|err| err.to_string())
+            .and_then(|report| {
+                serde_json::from_str::<Vec<Binary>>(&report)
+                    .or_else(|err| {
+                        // the output of a scan with a baseline
+                        serde_json::from_str::<BaselineReport>(&report)
+                            .map(|report| report.binaries)
+                            .map_err(|_| err)
+                    })
+                    .map_err(|err| err.to_string())
+            })
+            .unwrap_or_else(|err| {
+                eprintln!(
+                    "Cannot load baseline {}: {}",
+                    underline!(baseline),
+                    err
+                );
+                process::exit(1);
+            })
+    });
 
     let settings = output::Settings::set(
         #[cfg(feature = "color")]
         !args.get_flag("no-color"),
-        format,
+        output_format(&args),
         args.get_flag("maps"),
         libraries,
     );
 
-    let refresh_kind = RefreshKind::nothing().with_processes(
-        ProcessRefreshKind::nothing().with_cpu().with_exe(UpdateKind::Always),
-    );
-
-    if procall {
-        let system = System::new_with_specifics(refresh_kind);
-
-        let procs = parse_processes(system.processes().values(), libraries);
+    let group_by =
+        args.get_one::<String>("group-by").map(|key| match key.as_str() {
+            "container" => GroupBy::Container,
+            "unit" => GroupBy::Unit,
+            _ => GroupBy::Executable,
+        });
+    let source = ProcessSource {
+        #[cfg(target_os = "linux")]
+        procfs: args
+            .get_one::<String>("procfs")
+            .map_or_else(ProcFs::default, ProcFs::new),
+    };
 
-        print_process_results(&Processes::new(procs), &settings);
+    if let Some(kernel_config) = kernel_config {
+        let report = kernel(kernel_config, args.get_one::<String>("sysctl"));
+        print_kernel_results(&report, &settings);
+    } else if procall {
+        let procs = parse_processes(source.processes(), libraries, &source);
+        #[cfg(all(
+            feature = "maps",
+            any(target_os = "linux", target_os = "windows")
+        ))]
+        let procs: Vec<Process> = if args.get_flag("unsafe-maps") {
+            procs
+                .into_iter()
+                .filter(|process| {
+                    process
+                        .map_summary
+                        .as_ref()
+                        .is_some_and(proc::MapSummary::has_findings)
+                })
+                .collect()
+        } else {
+            procs
+        };
+
+        let procs = Processes::new(procs);
+        print_process_results(&procs, &settings, group_by);
+        enforce_policy(
+            policy.as_ref(),
+            procs.processes.iter().flat_map(process_binaries),
+        );
     } else if let Some(procids) = procids {
-        let procids: Vec<sysinfo::Pid> = procids
+        let procids: Vec<usize> = procids
             .split(',')
-            .map(|id| match id.parse::<sysinfo::Pid>() {
+            .map(|id| match id.parse::<usize>() {
                 Ok(id) => id,
                 Err(msg) => {
                     eprintln!("Invalid process ID {id}: {msg}");
@@ -902,51 +1604,67 @@ fn main() {
                 }
             })
             .collect();
-        let system = System::new_with_specifics(refresh_kind);
+        let mut running = source.processes();
 
         let procs = parse_processes(
             procids
                 .iter()
                 .filter_map(|&pid| {
-                    system.process(pid).or_else(|| {
+                    let Some(index) =
+                        running.iter().position(|process| process.pid == pid)
+                    else {
                         eprintln!("No process found with ID {pid}");
-                        None
-                    })
+                        return None;
+                    };
+                    Some(running.swap_remove(index))
                 })
                 .filter(|process| {
-                    if process.exe().is_some_and(Path::is_file) {
+                    if process.exe.as_ref().is_some_and(|exe| {
+                        source.image(process, exe).source.is_file()
+                    }) {
                         true
                     } else {
                         eprintln!(
                 "No valid executable found for process {} with ID {}: {}",
-                process.name().to_string_lossy(),
-                process.pid(),
-                process.exe().map_or("<unknown>".to_string(), |exe| exe.display().to_string())
+                process.name,
+                process.pid,
+                process.exe.as_ref().map_or("<unknown>".to_string(), |exe| exe.display().to_string())
             );
                         false
                     }
-                }),
+                })
+                .collect(),
             libraries,
+            &source,
         );
 
-        print_process_results(&Processes::new(procs), &settings);
+        let procs = Processes::new(procs);
+        print_process_results(&procs, &settings, group_by);
+        enforce_policy(
+            policy.as_ref(),
+            procs.processes.iter().flat_map(process_binaries),
+        );
     } else if let Some(procname) = procname {
-        let system = System::new_with_specifics(refresh_kind);
-
         let procs = parse_processes(
-            system
-                .processes_by_name(OsStr::new(procname))
-                // TODO: processes_by_name() should return a Iterator implementing the trait Send
-                .collect::<Vec<&sysinfo::Process>>()
-                .into_iter(),
+            source
+                .processes()
+                .into_iter()
+                .filter(|process| process.name.contains(procname.as_str()))
+                .collect(),
             libraries,
+            &source,
         );
 
         if procs.is_empty() {
             eprintln!("No process found matching name {procname}");
             process::exit(1);
         }
-        print_process_results(&Processes::new(procs), &settings);
+        let procs = Processes::new(procs);
+        print_process_results(&procs, &settings, group_by);
+        enforce_policy(
+            policy.as_ref(),
+            procs.processes.iter().flat_map(process_binaries),
+        );
     } else if let Some(directory) = directory {
         let directory_path = Path::new(directory);
 
@@ -955,7 +1673,13 @@ fn main() {
             process::exit(1);
         }
 
-        walk(directory_path, libraries, &settings);
+        let bins = walk(directory_path, libraries, sysroot);
+        let regressed =
+            print_scan_results(&bins, baseline.as_deref(), &settings);
+        enforce_policy(policy.as_ref(), &bins);
+        if regressed {
+            process::exit(1);
+        }
     } else if let Some(file) = file {
         let file_path = Path::new(file);
 
@@ -964,9 +1688,18 @@ fn main() {
             process::exit(1);
         }
 
-        match parse_single_file(file_path, libraries) {
-            Ok(result) => {
-                print_binary_results(&result, &settings);
+        match parse_single_file(file_path, libraries, sysroot) {
+            Ok(mut result) => {
+                check_search_dirs(&mut result, sysroot);
+                let regressed = print_scan_results(
+                    &result,
+                    baseline.as_deref(),
+                    &settings,
+                );
+                enforce_policy(policy.as_ref(), &result);
+                if regressed {
+                    process::exit(1);
+                }
             }
             Err(msg) => {
                 eprintln!(
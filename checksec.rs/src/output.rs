@@ -3,10 +3,13 @@ use colored::control;
 #[cfg(feature = "color")]
 use std::env;
 
+use crate::severity::SeverityProfile;
+
 pub enum Format {
     Text,
     Json,
     JsonPretty,
+    Sarif(SeverityProfile),
 }
 
 pub struct Settings {
@@ -558,6 +558,8 @@ pub trait Properties {
     fn symbol_count(&self) -> usize;
     // return if the binary has asan symbols
     fn has_asan(&self) -> bool;
+    /// get the PDB GUID of the `CodeView` debug directory
+    fn get_pdb_guid(&self) -> Option<String>;
 }
 impl Properties for PE<'_> {
     fn has_aslr(&self) -> ASLR {
@@ -794,4 +796,17 @@ impl Properties for PE<'_> {
     fn get_architecture(&self) -> String {
         header::machine_to_str(self.header.coff_header.machine).to_string()
     }
+    fn get_pdb_guid(&self) -> Option<String> {
+        let guid =
+            self.debug_data.as_ref()?.codeview_pdb70_debug_info?.signature;
+        // the first three fields of a GUID are little endian
+        Some(format!(
+            "{:08X}-{:04X}-{:04X}-{}-{}",
+            guid.pread_with::<u32>(0, scroll::LE).ok()?,
+            guid.pread_with::<u16>(4, scroll::LE).ok()?,
+            guid.pread_with::<u16>(6, scroll::LE).ok()?,
+            hex::encode_upper(&guid[8..10]),
+            hex::encode_upper(&guid[10..]),
+        ))
+    }
 }
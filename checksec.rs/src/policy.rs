@@ -0,0 +1,252 @@
+//! Evaluate checksec results against a policy of required mitigations
+//!
+//! A policy lists required property values per binary format, using the
+//! property names of the JSON output. A list of values accepts any of them,
+//! search path properties (`rpath`, `runpath`) are compared in their
+//! `:`-separated text form. ELF and Mach-O binaries additionally have the
+//! `insecure_search_paths` property, which is `true` if a search path
+//! directory is writable by others, missing or relative. Blobs which failed
+//! to parse violate every policy.
+//!
+//! ```toml
+//! [elf]
+//! relro = "Full"
+//! pie = ["PIE", "DSO"]
+//! canary = true
+//! rpath = "None"
+//! insecure_search_paths = false
+//!
+//! [pe]
+//! cfg = true
+//! aslr = "HighEntropyVa"
+//! ```
+use crate::binary::{BinSpecificProperties, BinType, Binary, Blob};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+/// Required property values per binary format
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub elf: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub pe: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub macho: BTreeMap<String, Value>,
+}
+
+/// Property of a blob which does not satisfy the policy
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Violation {
+    pub file: PathBuf,
+    pub binarytype: BinType,
+    /// `error` for blobs which failed to parse
+    pub property: String,
+    pub expected: Value,
+    /// `None` if the format has no such property, the error message for
+    /// blobs which failed to parse
+    pub actual: Option<Value>,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.actual {
+            Some(Value::String(message))
+                if self.binarytype == BinType::Error =>
+            {
+                write!(
+                    f,
+                    "{}: can not be checked, {}",
+                    self.file.display(),
+                    message
+                )
+            }
+            Some(actual) => write!(
+                f,
+                "{} ({}): {} is {}, expected {}",
+                self.file.display(),
+                self.binarytype,
+                self.property,
+                actual,
+                self.expected
+            ),
+            None => write!(
+                f,
+                "{} ({}): unknown property {}, expected {}",
+                self.file.display(),
+                self.binarytype,
+                self.property,
+                self.expected
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PolicyError {
+    /// I/O error
+    IO(io::Error),
+    /// Invalid TOML policy
+    Toml(toml::de::Error),
+    /// Invalid JSON policy
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IO(err) => err.fmt(f),
+            Self::Toml(err) => write!(f, "Invalid TOML policy: {err}"),
+            Self::Json(err) => write!(f, "Invalid JSON policy: {err}"),
+        }
+    }
+}
+
+impl From<io::Error> for PolicyError {
+    fn from(e: io::Error) -> Self {
+        PolicyError::IO(e)
+    }
+}
+
+impl Policy {
+    /// Parse a policy in TOML or, if it starts with `{`, JSON format.
+    ///
+    /// # Errors
+    /// Will fail if the policy is malformed or has unknown formats.
+    pub fn parse(content: &str) -> Result<Self, PolicyError> {
+        if content.trim_start().starts_with('{') {
+            serde_json::from_str(content).map_err(PolicyError::Json)
+        } else {
+            toml::from_str(content).map_err(PolicyError::Toml)
+        }
+    }
+
+    /// Read and parse a policy file, see `Policy::parse`.
+    ///
+    /// # Errors
+    /// Will fail if the file can not be read or the policy is malformed.
+    pub fn from_file(path: &Path) -> Result<Self, PolicyError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Check all blobs of the binaries and their libraries.
+    #[must_use]
+    pub fn check(&self, binaries: &[Binary]) -> Vec<Violation> {
+        binaries.iter().flat_map(|binary| self.check_binary(binary)).collect()
+    }
+
+    /// Check all blobs of a binary and its libraries.
+    #[must_use]
+    pub fn check_binary(&self, binary: &Binary) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = binary
+            .blobs
+            .iter()
+            .flat_map(|blob| self.check_blob(&binary.file, blob))
+            .collect();
+        violations.append(&mut self.check(&binary.libraries));
+        violations
+    }
+
+    /// Check a single blob of `file`. Blobs which failed to parse have no
+    /// properties and can not satisfy a policy, they are reported as a
+    /// violation of the `error` property.
+    #[must_use]
+    pub fn check_blob(&self, file: &Path, blob: &Blob) -> Vec<Violation> {
+        let (requirements, properties, insecure_search_paths) = match &blob
+            .properties
+        {
+            #[cfg(feature = "elf")]
+            BinSpecificProperties::Elf(elf) => (
+                &self.elf,
+                serde_json::to_value(elf),
+                Some(
+                    elf.rpath
+                        .iter()
+                        .chain(elf.runpath.iter())
+                        .any(crate::shared::Rpath::is_insecure),
+                ),
+            ),
+            #[cfg(feature = "pe")]
+            BinSpecificProperties::PE(pe) => {
+                (&self.pe, serde_json::to_value(pe), None)
+            }
+            #[cfg(feature = "macho")]
+            BinSpecificProperties::MachO(macho) => (
+                &self.macho,
+                serde_json::to_value(macho),
+                Some(
+                    macho.rpath.iter().any(crate::shared::Rpath::is_insecure),
+                ),
+            ),
+            BinSpecificProperties::Error(error) => {
+                return vec![Violation {
+                    file: file.to_path_buf(),
+                    binarytype: blob.binarytype.clone(),
+                    property: "error".to_string(),
+                    expected: Value::Null,
+                    actual: Some(Value::String(error.message.clone())),
+                }];
+            }
+        };
+        let Ok(Value::Object(mut properties)) = properties else {
+            return vec![];
+        };
+        if let Some(insecure) = insecure_search_paths {
+            properties.insert(
+                "insecure_search_paths".to_string(),
+                Value::Bool(insecure),
+            );
+        }
+
+        requirements
+            .iter()
+            .filter_map(|(property, expected)| {
+                let actual = properties.get(property).map(normalize);
+                if actual
+                    .as_ref()
+                    .is_some_and(|actual| satisfies(actual, expected))
+                {
+                    return None;
+                }
+                Some(Violation {
+                    file: file.to_path_buf(),
+                    binarytype: blob.binarytype.clone(),
+                    property: property.clone(),
+                    expected: expected.clone(),
+                    actual,
+                })
+            })
+            .collect()
+    }
+}
+
+fn satisfies(actual: &Value, expected: &Value) -> bool {
+    match expected {
+        Value::Array(allowed) => allowed.contains(actual),
+        expected => actual == expected,
+    }
+}
+
+// Compare `VecRpath` by its text form, e.g. "None" or "/opt/lib:$ORIGIN"
+pub(crate) fn normalize(value: &Value) -> Value {
+    let Some(Value::Array(paths)) = value.get("paths") else {
+        return value.clone();
+    };
+    Value::String(
+        paths
+            .iter()
+            .filter_map(|path| match path {
+                Value::String(path) => Some(path.as_str()),
+                Value::Object(variant) => {
+                    variant.values().next().and_then(Value::as_str)
+                }
+                _ => None,
+            })
+            .collect::<Vec<&str>>()
+            .join(":"),
+    )
+}
@@ -1,19 +1,30 @@
 #[cfg(all(
     feature = "color",
-    feature = "maps",
-    not(target_os = "macos")
+    any(target_os = "linux", all(feature = "maps", not(target_os = "macos")))
 ))]
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
-#[cfg(all(feature = "maps", not(target_os = "macos")))]
-use std::fmt;
-#[cfg(all(feature = "maps", target_os = "linux"))]
-use std::{fs, io::ErrorKind};
+#[cfg(target_os = "linux")]
+use std::fs;
 #[cfg(all(
     feature = "maps",
     any(target_os = "linux", target_os = "windows")
 ))]
-use std::{io::Error, path::PathBuf};
+use std::io::Error;
+#[cfg(all(feature = "maps", target_os = "linux"))]
+use std::io::ErrorKind;
+#[cfg(any(
+    target_os = "linux",
+    all(feature = "maps", target_os = "windows")
+))]
+use std::path::PathBuf;
+#[cfg(any(
+    target_os = "linux",
+    all(feature = "maps", not(target_os = "macos"))
+))]
+use std::{fmt, path::Path};
+#[cfg(target_os = "linux")]
+use std::{io, os::unix::fs::MetadataExt};
 
 #[cfg(all(feature = "maps", target_os = "windows"))]
 use windows::Win32::{
@@ -67,6 +78,7 @@ use std::{
 };
 
 use crate::binary::Binary;
+use crate::compare::Comparison;
 
 #[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
 #[derive(Deserialize, Serialize)]
@@ -76,6 +88,7 @@ pub struct Region {
 }
 #[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
 impl Region {
+    #[must_use]
     pub fn new(start: usize, end: usize) -> Self {
         Self { start, end }
     }
@@ -94,6 +107,7 @@ pub struct MapFlags {
 #[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
 impl MapFlags {
     #[cfg(target_os = "linux")]
+    #[must_use]
     pub fn new(flagstr: &str) -> Self {
         let r = flagstr.get(0..1) == Some("r");
         let w = flagstr.get(1..2) == Some("w");
@@ -321,6 +335,144 @@ impl fmt::Display for MapEntry {
     }
 }
 
+#[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
+impl MapEntry {
+    #[cfg(target_os = "linux")]
+    fn is_rwx(&self) -> bool {
+        self.flags.r && self.flags.w && self.flags.x
+    }
+    #[cfg(target_os = "windows")]
+    fn is_rwx(&self) -> bool {
+        self.flags.r && self.flags.w && self.flags.x && !self.flags.guard
+    }
+    #[cfg(target_os = "linux")]
+    fn is_stack(&self) -> bool {
+        self.pathname.as_deref() == Some(Path::new("[stack]"))
+    }
+    #[cfg(target_os = "windows")]
+    fn is_stack(&self) -> bool {
+        self.etype == MapType::Stack
+    }
+    #[cfg(target_os = "linux")]
+    fn is_heap(&self) -> bool {
+        self.pathname.as_deref() == Some(Path::new("[heap]"))
+    }
+    #[cfg(target_os = "windows")]
+    fn is_heap(&self) -> bool {
+        self.etype == MapType::Heap
+    }
+    // not backed by a file, including named anonymous mappings
+    // (`[anon:<name>]`) but not the stack, heap or kernel provided code like
+    // `[vdso]`
+    #[cfg(target_os = "linux")]
+    fn is_anonymous(&self) -> bool {
+        self.pathname.as_ref().is_none_or(|pathname| {
+            pathname.to_string_lossy().starts_with("[anon:")
+        })
+    }
+    #[cfg(target_os = "windows")]
+    fn is_anonymous(&self) -> bool {
+        self.etype == MapType::Private
+    }
+    fn is_file(&self) -> bool {
+        self.pathname.as_ref().is_some_and(|pathname| pathname.is_absolute())
+    }
+}
+
+/// Summary of the risky memory mappings of a process
+#[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct MapSummary {
+    /// Readable, writable and executable regions
+    pub rwx: usize,
+    /// Executable regions which are not backed by a file
+    pub anonymous_exec: usize,
+    /// The stack is executable
+    pub exec_stack: bool,
+    /// The heap is executable
+    pub exec_heap: bool,
+    /// Writable and executable regions backed by a file, readable ones are
+    /// counted in `rwx` as well
+    pub writable_file_exec: usize,
+}
+
+#[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
+impl MapSummary {
+    #[must_use]
+    pub fn new(maps: &[MapEntry]) -> Self {
+        let executable = || maps.iter().filter(|map| map.flags.x);
+        Self {
+            rwx: maps.iter().filter(|map| map.is_rwx()).count(),
+            anonymous_exec: executable()
+                .filter(|map| map.is_anonymous())
+                .count(),
+            exec_stack: executable().any(MapEntry::is_stack),
+            exec_heap: executable().any(MapEntry::is_heap),
+            writable_file_exec: executable()
+                .filter(|map| map.flags.w && map.is_file())
+                .count(),
+        }
+    }
+
+    /// The process has any risky mapping
+    #[must_use]
+    pub fn has_findings(&self) -> bool {
+        self.rwx > 0
+            || self.anonymous_exec > 0
+            || self.exec_stack
+            || self.exec_heap
+            || self.writable_file_exec > 0
+    }
+}
+
+#[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
+impl fmt::Display for MapSummary {
+    #[cfg(not(feature = "color"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RWX: {} Anonymous Exec: {} Exec Stack: {} Exec Heap: {} \
+            Writable File Exec: {}",
+            self.rwx,
+            self.anonymous_exec,
+            self.exec_stack,
+            self.exec_heap,
+            self.writable_file_exec
+        )
+    }
+    #[cfg(feature = "color")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |count: usize| {
+            if count == 0 {
+                count.to_string().green()
+            } else {
+                count.to_string().red()
+            }
+        };
+        let flag = |flag: bool| {
+            if flag {
+                flag.to_string().red()
+            } else {
+                flag.to_string().green()
+            }
+        };
+        write!(
+            f,
+            "{} {} {} {} {} {} {} {} {} {}",
+            "RWX:".bold(),
+            count(self.rwx),
+            "Anonymous Exec:".bold(),
+            count(self.anonymous_exec),
+            "Exec Stack:".bold(),
+            flag(self.exec_stack),
+            "Exec Heap:".bold(),
+            flag(self.exec_heap),
+            "Writable File Exec:".bold(),
+            count(self.writable_file_exec)
+        )
+    }
+}
+
 #[cfg(all(feature = "maps", target_os = "windows"))]
 struct WinProcInfo {
     pub pid: u32,
@@ -334,50 +486,585 @@ impl WinProcInfo {
     }
 }
 
-#[allow(clippy::unsafe_derive_deserialize)]
-#[derive(Deserialize, Serialize)]
-pub struct Process {
-    pub pid: usize,
-    pub binary: Binary,
-    #[cfg(all(
-        feature = "maps",
-        any(target_os = "linux", target_os = "windows")
-    ))]
-    pub maps: Option<Vec<MapEntry>>,
-    pub libraries: Option<Vec<Binary>>,
+// `personality(2)` flags, see `<linux/personality.h>`
+#[cfg(target_os = "linux")]
+const ADDR_NO_RANDOMIZE: u32 = 0x0004_0000;
+#[cfg(target_os = "linux")]
+const READ_IMPLIES_EXEC: u32 = 0x0040_0000;
+
+// capability names by `CAP_*` number, see `<linux/capability.h>`
+#[cfg(target_os = "linux")]
+const CAPABILITIES: [&str; 41] = [
+    "chown",
+    "dac_override",
+    "dac_read_search",
+    "fowner",
+    "fsetid",
+    "kill",
+    "setgid",
+    "setuid",
+    "setpcap",
+    "linux_immutable",
+    "net_bind_service",
+    "net_broadcast",
+    "net_admin",
+    "net_raw",
+    "ipc_lock",
+    "ipc_owner",
+    "sys_module",
+    "sys_rawio",
+    "sys_chroot",
+    "sys_ptrace",
+    "sys_pacct",
+    "sys_admin",
+    "sys_boot",
+    "sys_nice",
+    "sys_resource",
+    "sys_time",
+    "sys_tty_config",
+    "mknod",
+    "lease",
+    "audit_write",
+    "audit_control",
+    "setfcap",
+    "mac_override",
+    "mac_admin",
+    "syslog",
+    "wake_alarm",
+    "block_suspend",
+    "audit_read",
+    "perfmon",
+    "bpf",
+    "checkpoint_restore",
+];
+
+#[cfg(all(target_os = "linux", not(feature = "color")))]
+fn highlight(text: &str, secure: bool) -> String {
+    let _ = secure;
+    text.to_string()
 }
-impl Process {
-    #[cfg(any(not(feature = "maps"), target_os = "macos"))]
-    pub fn new(
-        pid: usize,
-        binary: Binary,
-        libraries: Option<Vec<Binary>>,
-    ) -> Self {
-        Self { pid, binary, libraries }
+
+#[cfg(all(target_os = "linux", feature = "color"))]
+fn highlight(text: &str, secure: bool) -> String {
+    if secure {
+        text.green().to_string()
+    } else {
+        text.red().to_string()
     }
-    #[cfg(all(
-        feature = "maps",
-        any(target_os = "linux", target_os = "windows")
-    ))]
+}
+
+/// Seccomp mode of a process
+#[cfg(target_os = "linux")]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Seccomp {
+    Disabled,
+    Strict,
+    Filter,
+}
+
+#[cfg(target_os = "linux")]
+impl fmt::Display for Seccomp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "Disabled"),
+            Self::Strict => write!(f, "Strict"),
+            Self::Filter => write!(f, "Filter"),
+        }
+    }
+}
+
+/// Capability sets of a process as bit masks of `CAP_*` numbers
+#[cfg(target_os = "linux")]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq,
+)]
+pub struct Capabilities {
+    pub effective: u64,
+    pub permitted: u64,
+    pub bounding: u64,
+}
+
+#[cfg(target_os = "linux")]
+impl Capabilities {
+    /// Names of the capabilities in `set`, `cap_<number>` for unknown ones
+    #[must_use]
+    pub fn names(set: u64) -> Vec<String> {
+        (0..64)
+            .filter(|bit| set & (1 << bit) != 0)
+            .map(|bit| match CAPABILITIES.get(bit) {
+                Some(name) => format!("cap_{name}"),
+                None => format!("cap_{bit}"),
+            })
+            .collect()
+    }
+
+    // `None`, `Full` if all known capabilities are set, the missing ones if
+    // most are set, otherwise their names
+    fn format(set: u64) -> String {
+        let known = (1_u64 << CAPABILITIES.len()) - 1;
+        let missing = known & !set;
+        if set == 0 {
+            highlight("None", true)
+        } else if missing == 0 {
+            highlight("Full", false)
+        } else if missing.count_ones() < set.count_ones() {
+            let missing = Self::names(missing).join(",");
+            highlight(&format!("Full except {missing}"), false)
+        } else {
+            highlight(&Self::names(set).join(","), false)
+        }
+    }
+}
+
+/// Runtime hardening state of a Linux process from `/proc/<pid>/status`
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Runtime {
+    pub seccomp: Option<Seccomp>,
+    /// Number of attached seccomp filters
+    pub seccomp_filters: Option<u32>,
+    pub no_new_privs: Option<bool>,
+    pub capabilities: Option<Capabilities>,
+    /// `personality(2)` flags, only readable by the owner of the process
+    pub personality: Option<u32>,
+}
+
+#[cfg(target_os = "linux")]
+impl Runtime {
+    /// Parse the `Seccomp`, `Seccomp_filters`, `NoNewPrivs` and `Cap*`
+    /// fields of `/proc/<pid>/status`.
+    #[must_use]
+    pub fn parse_status(status: &str) -> Self {
+        let fields: Vec<(&str, &str)> = status
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key, value.trim()))
+            .collect();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+        };
+        let capability = |name: &str| {
+            field(name).and_then(|value| u64::from_str_radix(value, 16).ok())
+        };
+        let capabilities = match (
+            capability("CapEff"),
+            capability("CapPrm"),
+            capability("CapBnd"),
+        ) {
+            (Some(effective), Some(permitted), Some(bounding)) => {
+                Some(Capabilities { effective, permitted, bounding })
+            }
+            _ => None,
+        };
+        Self {
+            seccomp: field("Seccomp").and_then(|mode| match mode {
+                "0" => Some(Seccomp::Disabled),
+                "1" => Some(Seccomp::Strict),
+                "2" => Some(Seccomp::Filter),
+                _ => None,
+            }),
+            seccomp_filters: field("Seccomp_filters")
+                .and_then(|count| count.parse().ok()),
+            no_new_privs: field("NoNewPrivs").map(|flag| flag == "1"),
+            capabilities,
+            personality: None,
+        }
+    }
+
+    /// ASLR is disabled for the process (`ADDR_NO_RANDOMIZE`)
+    #[must_use]
+    pub fn addr_no_randomize(&self) -> bool {
+        self.personality.is_some_and(|flags| flags & ADDR_NO_RANDOMIZE != 0)
+    }
+
+    /// Readable memory is executable (`READ_IMPLIES_EXEC`)
+    #[must_use]
+    pub fn read_implies_exec(&self) -> bool {
+        self.personality.is_some_and(|flags| flags & READ_IMPLIES_EXEC != 0)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl fmt::Display for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unknown = || "n/a".to_string();
+        let seccomp = self.seccomp.map_or_else(unknown, |seccomp| {
+            let text = match (seccomp, self.seccomp_filters) {
+                (Seccomp::Filter, Some(filters)) => {
+                    format!("{seccomp} ({filters})")
+                }
+                _ => seccomp.to_string(),
+            };
+            highlight(&text, seccomp != Seccomp::Disabled)
+        });
+        let no_new_privs = self
+            .no_new_privs
+            .map_or_else(unknown, |flag| highlight(&flag.to_string(), flag));
+        let (effective, permitted, bounding) = self.capabilities.map_or_else(
+            || (unknown(), unknown(), unknown()),
+            |caps| {
+                (
+                    Capabilities::format(caps.effective),
+                    Capabilities::format(caps.permitted),
+                    Capabilities::format(caps.bounding),
+                )
+            },
+        );
+        let personality = if self.personality.is_none() {
+            unknown()
+        } else if self.addr_no_randomize() || self.read_implies_exec() {
+            let mut flags = Vec::new();
+            if self.addr_no_randomize() {
+                flags.push("ADDR_NO_RANDOMIZE");
+            }
+            if self.read_implies_exec() {
+                flags.push("READ_IMPLIES_EXEC");
+            }
+            highlight(&flags.join(","), false)
+        } else {
+            highlight("Default", true)
+        };
+        write!(
+            f,
+            "Seccomp: {seccomp} NoNewPrivs: {no_new_privs} CapEff: {effective} \
+            CapPrm: {permitted} CapBnd: {bounding} Personality: {personality}"
+        )
+    }
+}
+
+/// Where a Linux process runs: its parent, user, cgroup and namespace
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Context {
+    pub ppid: Option<usize>,
+    /// Real user ID
+    pub uid: Option<u32>,
+    /// Name of the user in the `/etc/passwd` of the process' root
+    pub user: Option<String>,
+    /// Path of the process in the unified or the systemd cgroup hierarchy
+    pub cgroup: Option<String>,
+    /// ID of the container of the process, from its cgroup
+    pub container: Option<String>,
+    /// systemd service or scope of the process, from its cgroup
+    pub unit: Option<String>,
+    /// Inode of the PID namespace
+    pub namespace: Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+impl Context {
+    /// Parse the `PPid` and `Uid` fields of `/proc/<pid>/status` and the
+    /// cgroup path of `/proc/<pid>/cgroup`.
+    #[must_use]
+    pub fn parse(status: &str, cgroup: &str) -> Self {
+        let field = |name: &str| {
+            status
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(key, _)| *key == name)
+                .and_then(|(_, value)| value.split_whitespace().next())
+        };
+        let cgroup = Self::parse_cgroup(cgroup);
+        Self {
+            ppid: field("PPid").and_then(|ppid| ppid.parse().ok()),
+            uid: field("Uid").and_then(|uid| uid.parse().ok()),
+            user: None,
+            container: cgroup.as_deref().and_then(Self::container_id),
+            unit: cgroup.as_deref().and_then(Self::unit),
+            cgroup,
+            namespace: None,
+        }
+    }
+
+    /// Path of the unified (v2) hierarchy of a `/proc/<pid>/cgroup`, or the
+    /// systemd or first v1 hierarchy.
+    #[must_use]
+    pub fn parse_cgroup(cgroup: &str) -> Option<String> {
+        let hierarchies: Vec<(&str, &str)> = cgroup
+            .lines()
+            .filter_map(|line| {
+                let (_, line) = line.split_once(':')?;
+                line.split_once(':')
+            })
+            .collect();
+        hierarchies
+            .iter()
+            .find(|(controllers, _)| controllers.is_empty())
+            .or_else(|| {
+                hierarchies
+                    .iter()
+                    .find(|(controllers, _)| *controllers == "name=systemd")
+            })
+            .or(hierarchies.first())
+            .map(|(_, path)| (*path).to_string())
+    }
+
+    /// Container ID in a cgroup path, e.g. of `docker-<id>.scope`,
+    /// `cri-containerd-<id>.scope` or `/docker/<id>`
+    #[must_use]
+    pub fn container_id(cgroup: &str) -> Option<String> {
+        cgroup.rsplit('/').find_map(|name| {
+            let name = name.strip_suffix(".scope").unwrap_or(name);
+            let id = name.rsplit('-').next()?;
+            (id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| id.to_string())
+        })
+    }
+
+    /// The innermost systemd service or scope of a cgroup path
+    #[must_use]
+    pub fn unit(cgroup: &str) -> Option<String> {
+        cgroup
+            .rsplit('/')
+            .find(|name| {
+                Path::new(name)
+                    .extension()
+                    .is_some_and(|unit| unit == "service" || unit == "scope")
+            })
+            .map(str::to_string)
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unknown = || "n/a".to_string();
+        let user = match (&self.user, self.uid) {
+            (Some(user), _) => user.clone(),
+            (None, Some(uid)) => uid.to_string(),
+            (None, None) => unknown(),
+        };
+        write!(
+            f,
+            "PPID: {} User: {} Container: {} Unit: {} Namespace: {}",
+            self.ppid.map_or_else(unknown, |ppid| ppid.to_string()),
+            user,
+            self.container
+                .as_deref()
+                .map_or("None", |id| id.get(..12).unwrap_or(id)),
+            self.unit.as_deref().unwrap_or("None"),
+            self.namespace.map_or_else(unknown, |inode| inode.to_string())
+        )
+    }
+}
+
+/// A file mapped into a process
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MappedFile {
+    /// Path of the file as seen by the process
+    pub file: PathBuf,
+    /// Path to read the mapped image from
+    pub source: PathBuf,
+    /// The file was deleted or replaced since it was mapped
+    pub stale: bool,
+}
+
+/// Image of a process which was deleted or replaced on disk since it was
+/// loaded, e.g. by a package upgrade
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct StaleImage {
+    pub file: PathBuf,
+    /// Comparison of the running image with the file now at its path,
+    /// `None` if there is no such file or either could not be analyzed
+    pub replacement: Option<Comparison>,
+}
+
+impl StaleImage {
+    /// Compare the `running` image of `mapped` with the `replacement` now
+    /// at its path.
+    #[must_use]
     pub fn new(
-        pid: usize,
-        binary: Binary,
-        libraries: Option<Vec<Binary>>,
+        mapped: &MappedFile,
+        running: Option<&Binary>,
+        replacement: Option<&Binary>,
     ) -> Self {
-        match Process::parse_maps(pid) {
-            Ok(maps) => Self { pid, binary, maps: Some(maps), libraries },
-            Err(e) => {
-                eprintln!(
-                    "Failed to parse maps for process with ID {pid}: {e}"
-                );
-                Self { pid, binary, maps: None, libraries }
+        Self {
+            file: mapped.file.clone(),
+            replacement: running.zip(replacement).map(
+                |(running, replacement)| {
+                    let mut comparison = Comparison::new(running, replacement);
+                    comparison.left.clone_from(&mapped.source);
+                    comparison
+                },
+            ),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl fmt::Display for StaleImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match &self.replacement {
+            None => highlight("Deleted", false),
+            Some(comparison) if comparison.is_equal() => {
+                highlight("Replaced, properties unchanged", false)
+            }
+            Some(comparison) => {
+                let count: usize = comparison
+                    .blobs
+                    .iter()
+                    .map(|blob| blob.fields.len())
+                    .sum();
+                highlight(
+                    &format!("Replaced, {count} properties changed"),
+                    false,
+                )
             }
+        };
+        write!(f, "{} {state}", self.file.display())
+    }
+}
+
+// Split the ` (deleted)` marker of removed or replaced files off a path
+#[cfg(target_os = "linux")]
+fn strip_deleted(path: &Path) -> (PathBuf, bool) {
+    match path.to_str().and_then(|path| path.strip_suffix(" (deleted)")) {
+        Some(path) => (PathBuf::from(path), true),
+        None => (path.to_path_buf(), false),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_same_file(left: &fs::Metadata, right: &fs::Metadata) -> bool {
+    (left.dev(), left.ino()) == (right.dev(), right.ino())
+}
+
+/// A procfs tree to read processes from.
+///
+/// The default is `/proc` of the running system, another root allows to
+/// inspect the processes of a mounted container host or to replay a captured
+/// snapshot of `/proc`. Files of a process are read through its
+/// `<pid>/root` and `<pid>/map_files`, so the paths of processes in other
+/// mount namespaces resolve to their own files.
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProcFs {
+    pub root: PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl Default for ProcFs {
+    fn default() -> Self {
+        Self::new("/proc")
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl ProcFs {
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Path of the entry `name` of the process `pid`
+    #[must_use]
+    pub fn path(&self, pid: usize, name: &str) -> PathBuf {
+        self.root.join(pid.to_string()).join(name)
+    }
+
+    /// IDs of all processes, in ascending order.
+    ///
+    /// # Errors
+    /// Will fail if the root can not be read.
+    pub fn pids(&self) -> io::Result<Vec<usize>> {
+        let mut pids: Vec<usize> = fs::read_dir(&self.root)?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .collect();
+        pids.sort_unstable();
+        Ok(pids)
+    }
+
+    /// Command name of the process `pid`
+    #[must_use]
+    pub fn name(&self, pid: usize) -> Option<String> {
+        fs::read_to_string(self.path(pid, "comm"))
+            .ok()
+            .map(|comm| comm.trim_end().to_string())
+    }
+
+    /// Path of the executable of the process `pid` as seen by the process,
+    /// `None` for kernel threads
+    #[must_use]
+    pub fn exe(&self, pid: usize) -> Option<PathBuf> {
+        fs::read_link(self.path(pid, "exe"))
+            .ok()
+            .map(|exe| strip_deleted(&exe).0)
+    }
+
+    /// Executable of the process `pid`. If it was deleted or replaced since
+    /// the process started, the running image is read through `<pid>/exe`.
+    #[must_use]
+    pub fn executable(&self, pid: usize) -> Option<MappedFile> {
+        let link = self.path(pid, "exe");
+        let (file, deleted) = strip_deleted(&fs::read_link(&link).ok()?);
+        let resolved = self.resolve(pid, &file);
+        // the link resolves to the running image even if it was replaced
+        let stale = deleted
+            || fs::metadata(&link).is_ok_and(|running| {
+                resolved
+                    .as_ref()
+                    .ok()
+                    .and_then(|path| fs::metadata(path).ok())
+                    .is_none_or(|file| !is_same_file(&file, &running))
+            });
+        let source = match resolved {
+            Ok(path) if !stale => path,
+            _ => link,
+        };
+        Some(MappedFile { file, source, stale })
+    }
+
+    /// Path to read the file `path` of the process `pid` from, through the
+    /// root directory of the process if it differs from the own one.
+    ///
+    /// # Errors
+    /// Will fail if the process has another root directory and the file
+    /// can not be accessed through it. Files of other roots, e.g. of a
+    /// container or a captured snapshot, are never read from the host.
+    pub fn resolve(&self, pid: usize, path: &Path) -> io::Result<PathBuf> {
+        let Ok(relative) = path.strip_prefix("/") else {
+            return Ok(path.to_path_buf());
+        };
+        let resolved = self.sysroot(pid)?.join(relative);
+        fs::metadata(&resolved)?;
+        Ok(resolved)
+    }
+
+    /// Root directory to access the files of the process `pid` through,
+    /// `/` if it is the own one.
+    ///
+    /// # Errors
+    /// Will fail if the root directory of the process can not be accessed.
+    pub fn sysroot(&self, pid: usize) -> io::Result<PathBuf> {
+        let link = self.path(pid, "root");
+        let root = fs::metadata(&link)?;
+        if fs::metadata("/").is_ok_and(|own| is_same_file(&root, &own)) {
+            return Ok(PathBuf::from("/"));
         }
+        Ok(link)
     }
-    #[cfg(all(feature = "maps", target_os = "linux"))]
-    pub fn parse_maps(pid: usize) -> Result<Vec<MapEntry>, Error> {
+
+    /// Entry of `<pid>/map_files` for `map`, which refers to the mapped
+    /// file itself even if it was deleted. Opening it requires
+    /// `CAP_SYS_ADMIN`.
+    #[cfg(feature = "maps")]
+    #[must_use]
+    pub fn map_file(&self, pid: usize, map: &MapEntry) -> PathBuf {
+        self.path(pid, "map_files")
+            .join(format!("{:x}-{:x}", map.region.start, map.region.end))
+    }
+
+    /// Memory mappings of a process from `<pid>/maps`.
+    ///
+    /// # Errors
+    /// Will fail if the maps can not be read or have an invalid format.
+    #[cfg(feature = "maps")]
+    pub fn parse_maps(&self, pid: usize) -> Result<Vec<MapEntry>, Error> {
         let mut maps = Vec::new();
-        for line in fs::read_to_string(format!("/proc/{pid}/maps"))?.lines() {
+        for line in fs::read_to_string(self.path(pid, "maps"))?.lines() {
             let mut split_line = line.split_whitespace();
             let (start_str, end_str) = split_line
                 .next()
@@ -402,6 +1089,191 @@ impl Process {
         Ok(maps)
     }
 
+    /// Files of the executable mappings of the process `pid` besides its
+    /// executable `exe`. Deleted or replaced files are read through
+    /// `<pid>/map_files`.
+    #[cfg(feature = "maps")]
+    #[must_use]
+    pub fn libraries(
+        &self,
+        pid: usize,
+        maps: &[MapEntry],
+        exe: Option<&Path>,
+    ) -> Vec<MappedFile> {
+        let mut libraries: Vec<MappedFile> = Vec::new();
+        for map in maps.iter().filter(|map| map.flags.x) {
+            let Some(pathname) = &map.pathname else {
+                continue;
+            };
+            let (file, stale) = strip_deleted(pathname);
+            if !file.is_absolute()
+                || exe.is_some_and(|exe| file == exe)
+                || libraries.iter().any(|library| library.file == file)
+            {
+                continue;
+            }
+            // the mapped file itself if it is not at its path
+            let source = match self.resolve(pid, &file) {
+                Ok(path) if !stale => path,
+                _ => self.map_file(pid, map),
+            };
+            libraries.push(MappedFile { file, source, stale });
+        }
+        libraries
+    }
+
+    /// Parent, user, cgroup and PID namespace of the process `pid`.
+    ///
+    /// # Errors
+    /// Will fail if `<pid>/status` can not be read.
+    pub fn context(&self, pid: usize) -> io::Result<Context> {
+        let status = fs::read_to_string(self.path(pid, "status"))?;
+        let cgroup =
+            fs::read_to_string(self.path(pid, "cgroup")).unwrap_or_default();
+        let mut context = Context::parse(&status, &cgroup);
+        context.user = context.uid.and_then(|uid| {
+            let passwd = fs::read_to_string(
+                self.resolve(pid, Path::new("/etc/passwd")).ok()?,
+            )
+            .ok()?;
+            passwd.lines().find_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                (fields.nth(1)?.parse() == Ok(uid)).then(|| name.to_string())
+            })
+        });
+        // the link reads as `pid:[<inode>]`
+        context.namespace =
+            fs::read_link(self.path(pid, "ns/pid")).ok().and_then(|link| {
+                link.to_str()?
+                    .strip_prefix("pid:[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()
+            });
+        Ok(context)
+    }
+
+    /// Runtime state of the process `pid`.
+    ///
+    /// # Errors
+    /// Will fail if `<pid>/status` can not be read.
+    pub fn runtime(&self, pid: usize) -> io::Result<Runtime> {
+        let mut runtime = Runtime::parse_status(&fs::read_to_string(
+            self.path(pid, "status"),
+        )?);
+        runtime.personality =
+            fs::read_to_string(self.path(pid, "personality"))
+                .ok()
+                .and_then(|flags| u32::from_str_radix(flags.trim(), 16).ok());
+        Ok(runtime)
+    }
+
+    /// Process `pid` running `binary` with its runtime state and memory
+    /// mappings.
+    #[must_use]
+    pub fn process(
+        &self,
+        pid: usize,
+        binary: Binary,
+        libraries: Option<Vec<Binary>>,
+    ) -> Process {
+        #[cfg(feature = "maps")]
+        let maps = match self.parse_maps(pid) {
+            Ok(maps) => Some(maps),
+            Err(e) => {
+                eprintln!(
+                    "Failed to parse maps for process with ID {pid}: {e}"
+                );
+                None
+            }
+        };
+        let runtime = self.runtime(pid).ok();
+        Process {
+            pid,
+            binary,
+            context: self.context(pid).ok(),
+            runtime,
+            stale: Vec::new(),
+            #[cfg(feature = "maps")]
+            map_summary: maps.as_deref().map(MapSummary::new),
+            #[cfg(feature = "maps")]
+            maps,
+            libraries,
+        }
+    }
+}
+
+#[allow(clippy::unsafe_derive_deserialize)]
+#[derive(Deserialize, Serialize)]
+pub struct Process {
+    pub pid: usize,
+    pub binary: Binary,
+    /// Parent, user, cgroup and namespace, `None` if the process could not
+    /// be inspected
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub context: Option<Context>,
+    /// Runtime hardening state, `None` if the process could not be inspected
+    #[cfg(target_os = "linux")]
+    pub runtime: Option<Runtime>,
+    #[cfg(all(
+        feature = "maps",
+        any(target_os = "linux", target_os = "windows")
+    ))]
+    pub maps: Option<Vec<MapEntry>>,
+    /// Risky memory mappings, `None` if the maps could not be read
+    #[cfg(all(
+        feature = "maps",
+        any(target_os = "linux", target_os = "windows")
+    ))]
+    pub map_summary: Option<MapSummary>,
+    pub libraries: Option<Vec<Binary>>,
+    /// Images which were deleted or replaced on disk since they were loaded
+    #[serde(default)]
+    pub stale: Vec<StaleImage>,
+}
+impl Process {
+    #[cfg(not(any(
+        target_os = "linux",
+        all(feature = "maps", target_os = "windows")
+    )))]
+    pub fn new(
+        pid: usize,
+        binary: Binary,
+        libraries: Option<Vec<Binary>>,
+    ) -> Self {
+        Self { pid, binary, libraries, stale: Vec::new() }
+    }
+    /// Process `pid` of the running system, see `ProcFs::process`
+    #[cfg(target_os = "linux")]
+    #[must_use]
+    pub fn new(
+        pid: usize,
+        binary: Binary,
+        libraries: Option<Vec<Binary>>,
+    ) -> Self {
+        ProcFs::default().process(pid, binary, libraries)
+    }
+    #[cfg(all(feature = "maps", target_os = "windows"))]
+    #[must_use]
+    pub fn new(
+        pid: usize,
+        binary: Binary,
+        libraries: Option<Vec<Binary>>,
+    ) -> Self {
+        let maps = match Process::parse_maps(pid) {
+            Ok(maps) => Some(maps),
+            Err(e) => {
+                eprintln!(
+                    "Failed to parse maps for process with ID {pid}: {e}"
+                );
+                None
+            }
+        };
+        let map_summary = maps.as_deref().map(MapSummary::new);
+        Self { pid, binary, maps, map_summary, libraries, stale: Vec::new() }
+    }
     #[cfg(all(feature = "maps", target_os = "windows"))]
     fn parse_maps(pid: usize) -> Result<Vec<MapEntry>, Error> {
         let mut maps: Vec<MapEntry> = Vec::new();
@@ -467,9 +1339,181 @@ pub struct Processes {
     pub processes: Vec<Process>,
 }
 impl Processes {
+    #[must_use]
     pub fn new(processes: Vec<Process>) -> Self {
         Self { processes }
     }
+
+    /// Group the processes by `by`. Processes running the same file with
+    /// the same libraries share an entry of their group. Groups are sorted by their key, processes
+    /// without a container or unit come last.
+    #[must_use]
+    pub fn grouped(&self, by: GroupBy) -> ProcessGroups {
+        let mut groups: Vec<ProcessGroup> = Vec::new();
+        for process in &self.processes {
+            let key = by.key(process);
+            let index = if let Some(index) =
+                groups.iter().position(|group| group.key == key)
+            {
+                index
+            } else {
+                groups.push(ProcessGroup { key, executables: Vec::new() });
+                groups.len() - 1
+            };
+            let executables = &mut groups[index].executables;
+            match executables.iter_mut().find(|executable| {
+                executable.binary.file == process.binary.file
+                    && executable.binary.sha256 == process.binary.sha256
+                    && library_set(executable.libraries.as_deref())
+                        == library_set(process.libraries.as_deref())
+            }) {
+                Some(executable) => {
+                    executable.processes.push(GroupedProcess::new(process));
+                }
+                None => executables.push(GroupedExecutable {
+                    processes: vec![GroupedProcess::new(process)],
+                    binary: process.binary.clone(),
+                    libraries: process.libraries.clone(),
+                }),
+            }
+        }
+        for group in &mut groups {
+            for executable in &mut group.executables {
+                executable
+                    .processes
+                    .sort_unstable_by_key(|process| process.pid);
+            }
+        }
+        groups.sort_by(|left, right| match (&left.key, &right.key) {
+            (Some(left), Some(right)) => left.cmp(right),
+            (left, right) => right.is_some().cmp(&left.is_some()),
+        });
+        ProcessGroups { group_by: by, groups }
+    }
+}
+
+// Files and hashes of the libraries of a process, independent of their
+// order of loading
+fn library_set(
+    libraries: Option<&[Binary]>,
+) -> Option<Vec<(&Path, Option<&str>)>> {
+    let mut set: Vec<_> = libraries?
+        .iter()
+        .map(|library| (library.file.as_path(), library.sha256.as_deref()))
+        .collect();
+    set.sort_unstable();
+    Some(set)
+}
+
+/// Property to group processes by
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    Container,
+    Unit,
+    Executable,
+}
+
+impl GroupBy {
+    #[cfg(target_os = "linux")]
+    fn key(self, process: &Process) -> Option<String> {
+        let context = process.context.as_ref();
+        match self {
+            Self::Container => context?.container.clone(),
+            Self::Unit => context?.unit.clone(),
+            Self::Executable => {
+                Some(process.binary.file.display().to_string())
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    fn key(self, process: &Process) -> Option<String> {
+        match self {
+            Self::Container | Self::Unit => None,
+            Self::Executable => {
+                Some(process.binary.file.display().to_string())
+            }
+        }
+    }
+}
+
+/// State of a process which is not shared with the other processes running
+/// the same executable
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GroupedProcess {
+    pub pid: usize,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub context: Option<Context>,
+    #[cfg(target_os = "linux")]
+    #[serde(default)]
+    pub runtime: Option<Runtime>,
+    #[cfg(all(
+        feature = "maps",
+        any(target_os = "linux", target_os = "windows")
+    ))]
+    #[serde(default)]
+    pub map_summary: Option<MapSummary>,
+    #[serde(default)]
+    pub stale: Vec<StaleImage>,
+}
+
+impl GroupedProcess {
+    fn new(process: &Process) -> Self {
+        Self {
+            pid: process.pid,
+            #[cfg(target_os = "linux")]
+            context: process.context.clone(),
+            #[cfg(target_os = "linux")]
+            runtime: process.runtime.clone(),
+            #[cfg(all(
+                feature = "maps",
+                any(target_os = "linux", target_os = "windows")
+            ))]
+            map_summary: process.map_summary.clone(),
+            stale: process.stale.clone(),
+        }
+    }
+}
+
+/// An executable with the processes running it with the same libraries
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GroupedExecutable {
+    /// Processes running the executable, ordered by PID
+    pub processes: Vec<GroupedProcess>,
+    pub binary: Binary,
+    pub libraries: Option<Vec<Binary>>,
+}
+
+/// Processes of a container, unit or executable
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProcessGroup {
+    /// Container ID, unit or path of the executable, `None` for processes
+    /// outside of any container or unit
+    pub key: Option<String>,
+    pub executables: Vec<GroupedExecutable>,
+}
+
+impl ProcessGroup {
+    /// Number of processes in the group
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.executables
+            .iter()
+            .map(|executable| executable.processes.len())
+            .sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.executables.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProcessGroups {
+    pub group_by: GroupBy,
+    pub groups: Vec<ProcessGroup>,
 }
 
 #[cfg(all(feature = "maps", target_os = "windows"))]
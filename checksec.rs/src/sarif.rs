@@ -1,16 +1,40 @@
 //! Convert checksec report to sarif
+#[cfg(all(
+    feature = "maps",
+    any(target_os = "linux", target_os = "windows")
+))]
+use crate::proc::MapSummary;
 use crate::{
-    binary::{BinSpecificProperties, Binary},
-    elf, macho, pe,
+    binary::{BinSpecificProperties, Binary, Blob},
+    compression::get_sha256_hash,
+    diff::Matching,
+    elf,
+    kernel::{self, KernelReport, Status},
+    macho, pe,
+    proc::{Processes, StaleImage},
+    severity::{Level, SeverityProfile},
     shared::{Rpath, VecRpath},
+    VERSION,
 };
 use serde_json;
 use serde_sarif::sarif;
+use std::collections::BTreeMap;
 use std::path::Path;
 const SARIF_VERSION: &str = "2.1.0";
+const X86_ARCHS: &[&str] = &["X86_64", "386"];
+const AARCH64_ARCHS: &[&str] = &["AARCH64"];
 
 /// Converts a binary analysis result into a SARIF JSON report.
 ///
+/// Each `Binary` becomes a run, its libraries are added as artifacts nested
+/// below the artifact of the binary and results point to the artifact of the
+/// file they were found in. Files which failed to parse are reported as
+/// error notifications of the run's invocation instead of results.
+///
+/// The tool lists a rule for every check, results refer to it by index and
+/// carry a partial fingerprint of the SHA-256 of the file and the rule, so the
+/// same finding can be tracked across scans.
+///
 /// # Arguments
 ///
 /// * `results` - A reference to a vector of `Binary` structs. Each containing filename, blob information (individual reports), and libraries.
@@ -22,32 +46,503 @@ const SARIF_VERSION: &str = "2.1.0";
 ///
 /// Returns a `serde_json::Result<String>` containing the formatted SARIF JSON string on success.
 pub fn get_sarif_report(results: &[Binary]) -> serde_json::Result<String> {
-    let tool = get_tool_spec();
-    let sarif_runs: Vec<sarif::Run> = results
+    get_sarif_report_with_profile(results, &SeverityProfile::default())
+}
+
+/// Converts a binary analysis result into a SARIF JSON report, using
+/// `profile` for the level of failed checks. See `get_sarif_report`.
+///
+/// # Errors
+///
+/// This function returns an error when `serde_json` fails to serialize the sarif structure to json
+pub fn get_sarif_report_with_profile(
+    results: &[Binary],
+    profile: &SeverityProfile,
+) -> serde_json::Result<String> {
+    let tool = get_tool_spec(profile);
+    let sarif_runs = results
         .iter()
-        .map(|result| {
-            let results: Vec<sarif::Result> = result
-                .blobs
+        .map(|result| get_run(result, &tool, profile))
+        .collect::<serde_json::Result<Vec<sarif::Run>>>()?;
+    build_sarif_for_checksec(sarif_runs)
+}
+
+/// Converts the results of `current` into a SARIF JSON report, with the
+/// baseline state of each result relative to the results of `baseline`, e.g.
+/// a debug and a release build of the same program. Results with the same
+/// message are `unchanged`, with another message `updated` and results of
+/// checks the baseline lacks are `new`.
+///
+/// # Errors
+///
+/// This function returns an error when `serde_json` fails to serialize the sarif structure to json
+pub fn get_sarif_comparison(
+    baseline: &Binary,
+    current: &Binary,
+    profile: &SeverityProfile,
+) -> serde_json::Result<String> {
+    let mut run = get_run(current, &get_tool_spec(profile), profile)?;
+    set_baseline_states(run.results.iter_mut().flatten(), Some(baseline));
+    build_sarif_for_checksec(vec![run])
+}
+
+/// Converts the results of the scan `current` into a SARIF JSON report, with
+/// the baseline state of each result relative to the scan `baseline`, see
+/// `get_sarif_comparison`. Binaries and libraries are matched like in
+/// `Diff::new`, results of binaries the baseline lacks are `new` and the
+/// results of removed binaries are added as `absent` in runs of their own.
+///
+/// # Errors
+///
+/// This function returns an error when `serde_json` fails to serialize the sarif structure to json
+pub fn get_sarif_baseline_report(
+    baseline: &[Binary],
+    current: &[Binary],
+    profile: &SeverityProfile,
+) -> serde_json::Result<String> {
+    let matching = Matching::new(baseline, current);
+    let baselines: BTreeMap<String, &Binary> = matching
+        .pairs
+        .iter()
+        .map(|(before, after)| (after.file.to_string_lossy().into(), *before))
+        .collect();
+    let tool = get_tool_spec(profile);
+    let mut runs = current
+        .iter()
+        .map(|binary| {
+            let mut run = get_run(binary, &tool, profile)?;
+            let results = run.results.get_or_insert_with(Vec::new);
+            let mut files: Vec<Option<String>> =
+                results.iter().map(get_result_uri).collect();
+            files.dedup();
+            for file in files {
+                let before = file
+                    .as_ref()
+                    .and_then(|file| baselines.get(file).copied());
+                set_baseline_states(
+                    results
+                        .iter_mut()
+                        .filter(|result| get_result_uri(result) == file),
+                    before,
+                );
+            }
+            Ok(run)
+        })
+        .collect::<serde_json::Result<Vec<sarif::Run>>>()?;
+    for binary in baseline.iter().filter(|binary| {
+        matching.removed.iter().any(|removed| std::ptr::eq(*removed, *binary))
+    }) {
+        let mut run = get_run(binary, &tool, profile)?;
+        for result in run.results.iter_mut().flatten() {
+            result.baseline_state = Some(serde_json::Value::String(
+                sarif::ResultBaselineState::Absent.to_string(),
+            ));
+        }
+        runs.push(run);
+    }
+    build_sarif_for_checksec(runs)
+}
+
+// Set the baseline state of the results of a file relative to the results
+// of the `baseline` file, all results are new without one
+fn set_baseline_states<'a>(
+    results: impl Iterator<Item = &'a mut sarif::Result>,
+    baseline: Option<&Binary>,
+) {
+    let mut baseline_results: Vec<(String, String)> = baseline
+        .iter()
+        .flat_map(|baseline| &baseline.blobs)
+        .flat_map(get_blob_results)
+        .filter_map(|result| Some((result.rule_id?, result.message.text?)))
+        .collect();
+    for result in results {
+        let (Some(rule_id), Some(message)) =
+            (&result.rule_id, &result.message.text)
+        else {
+            continue;
+        };
+        let same_rule =
+            |(baseline_rule, _): &(String, String)| baseline_rule == rule_id;
+        let state = if let Some(index) = baseline_results
+            .iter()
+            .position(|baseline| same_rule(baseline) && &baseline.1 == message)
+        {
+            baseline_results.remove(index);
+            sarif::ResultBaselineState::Unchanged
+        } else if let Some(index) = baseline_results.iter().position(same_rule)
+        {
+            baseline_results.remove(index);
+            sarif::ResultBaselineState::Updated
+        } else {
+            sarif::ResultBaselineState::New
+        };
+        result.baseline_state =
+            Some(serde_json::Value::String(state.to_string()));
+    }
+}
+
+// URI of the artifact a result was found in
+fn get_result_uri(result: &sarif::Result) -> Option<String> {
+    result
+        .locations
+        .as_ref()?
+        .first()?
+        .physical_location
+        .as_ref()?
+        .artifact_location
+        .as_ref()?
+        .uri
+        .clone()
+}
+
+/// Converts the kernel hardening checks into a SARIF JSON report, using
+/// `profile` for the level of failed checks.
+///
+/// Every recommended option is a rule, results point to the configuration
+/// or sysctl snapshot the value was read from. Settings missing from the
+/// sysctl snapshot are notes.
+///
+/// # Errors
+///
+/// This function returns an error when `serde_json` fails to serialize the sarif structure to json
+pub fn get_sarif_kernel_report(
+    report: &KernelReport,
+    profile: &SeverityProfile,
+) -> serde_json::Result<String> {
+    let rules = kernel::CHECKS
+        .iter()
+        .map(|check| {
+            let level = profile.level(check.name(), check.level.into());
+            sarif::ReportingDescriptor::builder()
+                .id(check.name().to_string())
+                .short_description(get_message_string(check.description))
+                .help(get_message_string(&check.remediation()))
+                .default_configuration(get_configuration(level.to_string()))
+                .build()
+        })
+        .collect();
+    let mut artifacts = vec![get_file_artifact(&report.config)];
+    artifacts.extend(report.sysctl.as_deref().map(get_file_artifact));
+    let results = report
+        .checks
+        .iter()
+        .map(|check| {
+            let rule_index = kernel::CHECKS
                 .iter()
-                .flat_map(|blob| match &blob.properties {
-                    BinSpecificProperties::Elf(elf) => create_elf_results(elf),
-                    BinSpecificProperties::PE(pe) => create_pe_results(pe),
-                    BinSpecificProperties::MachO(macho) => create_macho_results(macho),
-                    BinSpecificProperties::Error(_) => unreachable!(
-                        "Sarif reports should only be generated for non-error reports."
-                    ),
+                .position(|rule| rule.name() == check.name);
+            let level = match check.status {
+                Status::Pass => sarif::ResultLevel::None,
+                Status::Unknown => sarif::ResultLevel::Note,
+                Status::Fail => profile.level(
+                    &check.name,
+                    rule_index.map_or(sarif::ResultLevel::Warning, |index| {
+                        kernel::CHECKS[index].level.into()
+                    }),
+                ),
+            };
+            let (file, index) = match (check.source, &report.sysctl) {
+                (kernel::Source::Sysctl, Some(sysctl)) => (sysctl, 1),
+                _ => (&report.config, 0),
+            };
+            let mut result = sarif::Result::builder()
+                .rule_id(check.name.clone())
+                .message(
+                    sarif::Message::builder()
+                        .text(format!(
+                            "{}: {} (expected {})",
+                            check.name,
+                            check.value.as_deref().unwrap_or("not set"),
+                            check.expected
+                        ))
+                        .build(),
+                )
+                .level(level)
+                .locations(vec![get_file_location(file, index)])
+                .build();
+            result.rule_index =
+                rule_index.and_then(|index| i64::try_from(index).ok());
+            // kernel settings are identified by the file they are read from
+            result.partial_fingerprints = Some(get_partial_fingerprints(
+                &file.to_string_lossy(),
+                &check.name,
+            ));
+            result
+        })
+        .collect::<Vec<_>>();
+    let run = sarif::Run::builder()
+        .tool(get_tool(rules))
+        .invocations(vec![get_invocation(Vec::new())])
+        .artifacts(artifacts)
+        .results(results)
+        .build();
+    build_sarif_for_checksec(vec![run])
+}
+
+/// Converts the analysis of running processes into a SARIF JSON report,
+/// using `profile` for the level of failed checks.
+///
+/// Each process becomes a run of its binary with the loaded libraries nested
+/// below it. Risky memory mappings of the process are results pointing to
+/// the artifact of the binary, deleted or replaced images to the artifact of
+/// the file.
+///
+/// # Errors
+///
+/// This function returns an error when `serde_json` fails to serialize the sarif structure to json
+pub fn get_sarif_process_report(
+    processes: &Processes,
+    profile: &SeverityProfile,
+) -> serde_json::Result<String> {
+    let tool = get_tool_spec(profile);
+    let sarif_runs = processes
+        .processes
+        .iter()
+        .map(|process| {
+            let mut binary = process.binary.clone();
+            binary.libraries = process.libraries.clone().unwrap_or_default();
+            let mut run = get_run(&binary, &tool, profile)?;
+            let stale_results: Vec<sarif::Result> = process
+                .stale
+                .iter()
+                .map(|image| {
+                    let file = image.file.to_string_lossy();
+                    let artifact =
+                        run.artifacts.iter().flatten().position(|artifact| {
+                            artifact.location.as_ref().is_some_and(
+                                |location| {
+                                    location.uri.as_deref() == Some(&*file)
+                                },
+                            )
+                        });
+                    // the running image is the artifact, hashed when it was
+                    // analyzed
+                    let file_hash = artifact
+                        .and_then(|index| {
+                            run.artifacts.iter().flatten().nth(index)
+                        })
+                        .and_then(|artifact| artifact.hashes.as_ref())
+                        .and_then(|hashes| hashes.get("sha-256"))
+                        .map_or_else(|| file.to_string(), Clone::clone);
+                    let index = artifact.unwrap_or(0);
+                    let mut result = create_stale_result(image);
+                    result.rule_index = RULES
+                        .iter()
+                        .position(|rule| rule.id == "stale_image")
+                        .and_then(|position| i64::try_from(position).ok());
+                    result.partial_fingerprints = Some(
+                        get_partial_fingerprints(&file_hash, "stale_image"),
+                    );
+                    result.level = result
+                        .level
+                        .take()
+                        .map(|level| profile.level("stale_image", level));
+                    result.locations = Some(vec![get_file_location(
+                        &image.file,
+                        i64::try_from(index).unwrap_or(0),
+                    )]);
+                    result
                 })
                 .collect();
-            sarif::Run::builder()
-                .tool(tool.clone())
-                .artifacts(vec![get_file_artifact(&result.file)])
-                .results(results)
-                .build()
+            run.results.get_or_insert_with(Vec::new).extend(stale_results);
+            #[cfg(all(
+                feature = "maps",
+                any(target_os = "linux", target_os = "windows")
+            ))]
+            if let Some(summary) = &process.map_summary {
+                let location = get_file_location(&binary.file, 0);
+                let file_hash = get_file_hash(&binary);
+                run.results.get_or_insert_with(Vec::new).extend(
+                    create_map_results(summary).into_iter().map(
+                        |mut result| {
+                            if let Some(rule_id) = &result.rule_id {
+                                result.rule_index = RULES
+                                    .iter()
+                                    .position(|rule| rule.id == rule_id)
+                                    .and_then(|position| {
+                                        i64::try_from(position).ok()
+                                    });
+                                result.partial_fingerprints =
+                                    Some(get_partial_fingerprints(
+                                        &file_hash, rule_id,
+                                    ));
+                                result.level =
+                                    result.level.take().map(|level| {
+                                        profile.level(rule_id, level)
+                                    });
+                            }
+                            result.locations = Some(vec![location.clone()]);
+                            result
+                        },
+                    ),
+                );
+            }
+            Ok(run)
         })
-        .collect();
+        .collect::<serde_json::Result<Vec<sarif::Run>>>()?;
     build_sarif_for_checksec(sarif_runs)
 }
 
+// Build the run of a binary and its libraries
+fn get_run(
+    binary: &Binary,
+    tool: &sarif::Tool,
+    profile: &SeverityProfile,
+) -> serde_json::Result<sarif::Run> {
+    let mut artifacts = Vec::new();
+    let mut results = Vec::new();
+    let mut notifications = Vec::new();
+    add_binary_results(
+        binary,
+        None,
+        profile,
+        &mut artifacts,
+        &mut results,
+        &mut notifications,
+    )?;
+    Ok(sarif::Run::builder()
+        .tool(tool.clone())
+        .invocations(vec![get_invocation(notifications)])
+        .artifacts(artifacts)
+        .results(results)
+        .build())
+}
+
+// Add the artifact and results of a binary, followed by the ones of its
+// libraries with the binary as parent artifact. Blobs which failed to parse
+// are added as notifications.
+fn add_binary_results(
+    binary: &Binary,
+    parent_index: Option<i64>,
+    profile: &SeverityProfile,
+    artifacts: &mut Vec<sarif::Artifact>,
+    results: &mut Vec<sarif::Result>,
+    notifications: &mut Vec<sarif::Notification>,
+) -> serde_json::Result<()> {
+    let index = i64::try_from(artifacts.len()).unwrap_or(i64::MAX);
+    let mut artifact = get_file_artifact(&binary.file);
+    artifact.parent_index = parent_index;
+    artifact.hashes = binary.sha256.as_ref().map(|sha256| {
+        BTreeMap::from([("sha-256".to_string(), sha256.clone())])
+    });
+    artifact.length = binary.size.and_then(|size| i64::try_from(size).ok());
+    artifacts.push(artifact);
+
+    let location = get_file_location(&binary.file, index);
+    let file_hash = get_file_hash(binary);
+    for (blob_index, blob) in binary.blobs.iter().enumerate() {
+        if let BinSpecificProperties::Error(error) = &blob.properties {
+            notifications
+                .push(get_error_notification(&error.message, &location));
+            continue;
+        }
+        // the blobs of a fat binary share the hash of the file
+        let subject = if binary.blobs.len() > 1 {
+            format!("{file_hash}#{blob_index}")
+        } else {
+            file_hash.clone()
+        };
+        results.extend(get_blob_results(blob).into_iter().map(
+            |mut result| {
+                if let Some(rule_id) = &result.rule_id {
+                    result.rule_index = RULES
+                        .iter()
+                        .position(|rule| rule.id == rule_id)
+                        .and_then(|position| i64::try_from(position).ok());
+                    result.partial_fingerprints =
+                        Some(get_partial_fingerprints(&subject, rule_id));
+                    result.level = result.level.take().map(|level| {
+                        if is_applicable(blob, rule_id) {
+                            profile.level(rule_id, level)
+                        } else {
+                            profile.inapplicable_level(rule_id, level)
+                        }
+                    });
+                }
+                result.locations = Some(vec![location.clone()]);
+                result
+            },
+        ));
+    }
+
+    for library in &binary.libraries {
+        add_binary_results(
+            library,
+            Some(index),
+            profile,
+            artifacts,
+            results,
+            notifications,
+        )?;
+    }
+    Ok(())
+}
+
+// Results of the checks of a blob, without locations. Blobs which failed to
+// parse have no results.
+pub(crate) fn get_blob_results(blob: &Blob) -> Vec<sarif::Result> {
+    match &blob.properties {
+        BinSpecificProperties::Elf(elf) => create_elf_results(elf),
+        BinSpecificProperties::PE(pe) => create_pe_results(pe),
+        BinSpecificProperties::MachO(macho) => create_macho_results(macho),
+        BinSpecificProperties::Error(_) => vec![],
+    }
+}
+
+// SHA-256 of the analyzed file, its path if it was not hashed
+fn get_file_hash(binary: &Binary) -> String {
+    binary
+        .sha256
+        .clone()
+        .unwrap_or_else(|| binary.file.to_string_lossy().into_owned())
+}
+
+// Fingerprint a result by the analyzed file and the rule it belongs to
+fn get_partial_fingerprints(
+    subject: &str,
+    rule_id: &str,
+) -> BTreeMap<String, String> {
+    let mut fingerprint = subject.as_bytes().to_vec();
+    fingerprint.extend_from_slice(rule_id.as_bytes());
+    BTreeMap::from([(
+        "binaryRuleHash/v1".to_string(),
+        hex::encode(get_sha256_hash(&fingerprint)),
+    )])
+}
+
+// create a sarif Notification for a file which could not be analyzed
+fn get_error_notification(
+    error: &str,
+    location: &sarif::Location,
+) -> sarif::Notification {
+    sarif::Notification::builder()
+        .descriptor(
+            sarif::ReportingDescriptorReference::builder()
+                .id("parse_error".to_string())
+                .build(),
+        )
+        .message(sarif::Message::builder().text(error.to_string()).build())
+        .level(serde_json::Value::String(
+            sarif::NotificationLevel::Error.to_string(),
+        ))
+        .locations(vec![location.clone()])
+        .build()
+}
+
+// create a sarif Invocation which failed if any file could not be analyzed
+fn get_invocation(
+    notifications: Vec<sarif::Notification>,
+) -> sarif::Invocation {
+    if notifications.is_empty() {
+        return sarif::Invocation::builder()
+            .execution_successful(true)
+            .build();
+    }
+    sarif::Invocation::builder()
+        .execution_successful(false)
+        .tool_execution_notifications(notifications)
+        .build()
+}
+
 // create a sarif Artifact with the name of the file included
 fn get_file_artifact(filename: &Path) -> sarif::Artifact {
     sarif::Artifact::builder()
@@ -59,15 +554,61 @@ fn get_file_artifact(filename: &Path) -> sarif::Artifact {
         .build()
 }
 
+// create a sarif Location referring to the artifact at `index` of the run
+fn get_file_location(filename: &Path, index: i64) -> sarif::Location {
+    sarif::Location::builder()
+        .physical_location(
+            sarif::PhysicalLocation::builder()
+                .artifact_location(
+                    sarif::ArtifactLocation::builder()
+                        .uri(filename.to_string_lossy().to_string())
+                        .index(index)
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
 // Perform initial setup of the sarif Tool type
-fn get_tool_spec() -> sarif::Tool {
+fn get_tool_spec(profile: &SeverityProfile) -> sarif::Tool {
+    get_tool(RULES.iter().map(|rule| rule.descriptor(profile)).collect())
+}
+
+// sarif Tool with the given rules
+fn get_tool(rules: Vec<sarif::ReportingDescriptor>) -> sarif::Tool {
     sarif::Tool::builder()
         .driver(
-            sarif::ToolComponent::builder().name("checksec-anywhere").build(),
+            sarif::ToolComponent::builder()
+                .name("checksec-anywhere")
+                .version(VERSION.to_string())
+                .semantic_version(VERSION.to_string())
+                .information_uri(env!("CARGO_PKG_HOMEPAGE").to_string())
+                .rules(rules)
+                .notifications(vec![sarif::ReportingDescriptor::builder()
+                    .id("parse_error".to_string())
+                    .short_description(get_message_string(
+                        "File could not be analyzed",
+                    ))
+                    .default_configuration(get_configuration(
+                        sarif::NotificationLevel::Error.to_string(),
+                    ))
+                    .build()])
+                .build(),
         )
         .build()
 }
 
+fn get_message_string(text: &str) -> sarif::MultiformatMessageString {
+    sarif::MultiformatMessageString::builder().text(text.to_string()).build()
+}
+
+fn get_configuration(level: String) -> sarif::ReportingConfiguration {
+    sarif::ReportingConfiguration::builder()
+        .level(serde_json::Value::String(level))
+        .build()
+}
+
 // Wrap a vector of runs into a full sarif report and convert it to json
 fn build_sarif_for_checksec(
     runs: Vec<sarif::Run>,
@@ -86,7 +627,7 @@ fn build_sarif_for_checksec(
 fn create_elf_results(
     elf_result: &elf::CheckSecResults,
 ) -> Vec<sarif::Result> {
-    vec![
+    let mut results = vec![
         sarif::Result::builder()
             .rule_id("architecture".to_string())
             .message(
@@ -205,6 +746,54 @@ fn create_elf_results(
                 sarif::ResultLevel::Warning
             })
             .build(),
+        sarif::Result::builder()
+            .rule_id("IBT".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "CET Indirect Branch Tracking enabled: {}",
+                        elf_result.ibt
+                    ))
+                    .build(),
+            )
+            .level(check_feature(elf_result.ibt))
+            .build(),
+        sarif::Result::builder()
+            .rule_id("SHSTK".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "CET Shadow Stack enabled: {}",
+                        elf_result.shstk
+                    ))
+                    .build(),
+            )
+            .level(check_feature(elf_result.shstk))
+            .build(),
+        sarif::Result::builder()
+            .rule_id("BTI".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "Branch Target Identification enabled: {}",
+                        elf_result.bti
+                    ))
+                    .build(),
+            )
+            .level(check_feature(elf_result.bti))
+            .build(),
+        sarif::Result::builder()
+            .rule_id("PAC".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "Pointer Authentication enabled: {}",
+                        elf_result.pac
+                    ))
+                    .build(),
+            )
+            .level(check_feature(elf_result.pac))
+            .build(),
         sarif::Result::builder()
             .rule_id("Fortify".to_string())
             .message(
@@ -275,6 +864,44 @@ fn create_elf_results(
                 elf::Nx::Disabled => sarif::ResultLevel::Warning,
             })
             .build(),
+        sarif::Result::builder()
+            .rule_id("rwx_segments".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(if elf_result.rwx_segments.is_empty() {
+                        "Writable and executable segments: None".to_string()
+                    } else {
+                        format!(
+                            "Writable and executable segments: {}",
+                            elf_result
+                                .rwx_segments
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })
+                    .build(),
+            )
+            .level(if elf_result.rwx_segments.is_empty() {
+                sarif::ResultLevel::None
+            } else {
+                sarif::ResultLevel::Error
+            })
+            .build(),
+        sarif::Result::builder()
+            .rule_id("textrel".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!("Text relocations: {}", elf_result.textrel))
+                    .build(),
+            )
+            .level(if elf_result.textrel {
+                sarif::ResultLevel::Error
+            } else {
+                sarif::ResultLevel::None
+            })
+            .build(),
         sarif::Result::builder()
             .rule_id("PIE".to_string())
             .message(
@@ -288,7 +915,7 @@ fn create_elf_results(
             .level(match elf_result.pie {
                 elf::PIE::None => sarif::ResultLevel::Warning,
                 elf::PIE::DSO | elf::PIE::REL => sarif::ResultLevel::Note,
-                elf::PIE::PIE => sarif::ResultLevel::None,
+                elf::PIE::PIE | elf::PIE::Module => sarif::ResultLevel::None,
             })
             .build(),
         sarif::Result::builder()
@@ -385,6 +1012,153 @@ fn create_elf_results(
                 sarif::ResultLevel::Warning
             })
             .build(),
+    ];
+    if let Some(module) = &elf_result.kernel_module {
+        results.extend(create_kernel_module_results(module));
+    }
+    results
+}
+
+// Convert the properties of a Linux kernel module into a vector of results
+fn create_kernel_module_results(
+    module: &elf::KernelModule,
+) -> Vec<sarif::Result> {
+    vec![
+        sarif::Result::builder()
+            .rule_id("module_signature".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "Module signature appended: {}",
+                        module.signed
+                    ))
+                    .build(),
+            )
+            .level(if module.signed {
+                sarif::ResultLevel::None
+            } else {
+                sarif::ResultLevel::Warning
+            })
+            .build(),
+        sarif::Result::builder()
+            .rule_id("retpoline".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "Built with retpolines: {}",
+                        module.retpoline
+                    ))
+                    .build(),
+            )
+            .level(check_feature(module.retpoline))
+            .build(),
+        sarif::Result::builder()
+            .rule_id("vermagic".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "Kernel version magic: {}",
+                        module
+                            .vermagic
+                            .as_deref()
+                            .unwrap_or("None")
+                            .trim_end()
+                    ))
+                    .build(),
+            )
+            .level(sarif::ResultLevel::Note)
+            .build(),
+        sarif::Result::builder()
+            .rule_id("module_license".to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!(
+                        "Module license: {}",
+                        module.license.as_deref().unwrap_or("None")
+                    ))
+                    .build(),
+            )
+            .level(sarif::ResultLevel::Note)
+            .build(),
+    ]
+}
+
+// Convert a deleted or replaced image of a process into a result
+fn create_stale_result(image: &StaleImage) -> sarif::Result {
+    let file = image.file.display();
+    let text = match &image.replacement {
+        None => format!("{file} was deleted or replaced since it was loaded"),
+        Some(comparison) if comparison.is_equal() => format!(
+            "{file} was replaced since it was loaded, properties unchanged"
+        ),
+        Some(comparison) => format!(
+            "{file} was replaced since it was loaded, changed: {}",
+            comparison
+                .blobs
+                .iter()
+                .flat_map(|blob| &blob.fields)
+                .map(|field| field.field.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    sarif::Result::builder()
+        .rule_id("stale_image".to_string())
+        .message(sarif::Message::builder().text(text).build())
+        .level(sarif::ResultLevel::Warning)
+        .build()
+}
+
+// Convert the memory mappings of a process into a vector of results
+#[cfg(all(feature = "maps", any(target_os = "linux", target_os = "windows")))]
+fn create_map_results(summary: &MapSummary) -> Vec<sarif::Result> {
+    let count = |rule_id: &str, text: &str, count: usize, level| {
+        sarif::Result::builder()
+            .rule_id(rule_id.to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!("{text}: {count}"))
+                    .build(),
+            )
+            .level(if count == 0 { sarif::ResultLevel::None } else { level })
+            .build()
+    };
+    let flag = |rule_id: &str, text: &str, flag: bool| {
+        sarif::Result::builder()
+            .rule_id(rule_id.to_string())
+            .message(
+                sarif::Message::builder()
+                    .text(format!("{text}: {flag}"))
+                    .build(),
+            )
+            .level(if flag {
+                sarif::ResultLevel::Error
+            } else {
+                sarif::ResultLevel::None
+            })
+            .build()
+    };
+    vec![
+        count(
+            "rwx_mappings",
+            "Readable, writable and executable mappings",
+            summary.rwx,
+            sarif::ResultLevel::Error,
+        ),
+        count(
+            "anonymous_exec_mappings",
+            "Anonymous executable mappings",
+            summary.anonymous_exec,
+            sarif::ResultLevel::Warning,
+        ),
+        flag("exec_stack_mapping", "Executable stack", summary.exec_stack),
+        flag("exec_heap_mapping", "Executable heap", summary.exec_heap),
+        count(
+            "writable_file_exec_mappings",
+            "Writable file-backed executable mappings",
+            summary.writable_file_exec,
+            sarif::ResultLevel::Error,
+        ),
     ]
 }
 
@@ -866,21 +1640,734 @@ fn create_macho_results(
 }
 
 // Check if the elements in an rpath/runpath are nontrivial and should be given warning.
+// Writable, missing or relative directories allow library hijacking.
 fn check_rpath(paths: &VecRpath) -> sarif::ResultLevel {
     if paths.is_empty() {
         return sarif::ResultLevel::None;
     }
+    if paths.iter().any(Rpath::is_insecure) {
+        return sarif::ResultLevel::Error;
+    }
     if paths.len() != 1 {
         return sarif::ResultLevel::Warning;
     }
     match &paths[0] {
-        Rpath::Yes(path) | Rpath::YesRW(path) => {
+        Rpath::Yes(path) => {
             if path == "None" {
                 sarif::ResultLevel::None
             } else {
                 sarif::ResultLevel::Warning
             }
         }
-        Rpath::None => sarif::ResultLevel::None,
+        _ => sarif::ResultLevel::None,
+    }
+}
+
+// Architecture-specific mitigations (CET, BTI/PAC) are only reported as
+// missing on the architectures that implement them.
+fn check_feature(enabled: bool) -> sarif::ResultLevel {
+    if enabled {
+        sarif::ResultLevel::None
+    } else {
+        sarif::ResultLevel::Warning
+    }
+}
+
+// Failed checks which do not apply to the target of a blob: CPU features of
+// other architectures and SafeSEH of 64-bit images, which use table-based
+// exception handling
+fn is_applicable(blob: &Blob, rule_id: &str) -> bool {
+    match &blob.properties {
+        BinSpecificProperties::Elf(elf) => match rule_id {
+            "IBT" | "SHSTK" | "retpoline" => {
+                X86_ARCHS.contains(&elf.architecture.as_str())
+            }
+            "BTI" | "PAC" => {
+                AARCH64_ARCHS.contains(&elf.architecture.as_str())
+            }
+            _ => true,
+        },
+        BinSpecificProperties::PE(pe) => {
+            rule_id != "safeseh" || pe.bitness != 64
+        }
+        _ => true,
     }
 }
+
+/// The rule id is a check of binaries or of the kernel, e.g. of a severity
+/// profile.
+#[must_use]
+pub fn is_rule(rule_id: &str) -> bool {
+    RULES.iter().any(|rule| rule.id == rule_id)
+        || kernel::CHECKS.iter().any(|check| check.names.contains(&rule_id))
+}
+
+// Metadata of a check, reported as sarif reportingDescriptor. Rules with the
+// same meaning share an id across binary formats.
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    short_description: &'static str,
+    full_description: &'static str,
+    remediation: &'static str,
+    help_uri: Option<&'static str>,
+    // level of a result when the check fails
+    level: Level,
+}
+
+impl Rule {
+    fn descriptor(
+        &self,
+        profile: &SeverityProfile,
+    ) -> sarif::ReportingDescriptor {
+        let level = profile.level(self.id, self.level.into());
+        let mut descriptor = sarif::ReportingDescriptor::builder()
+            .id(self.id.to_string())
+            .name(self.name.to_string())
+            .short_description(get_message_string(self.short_description))
+            .full_description(get_message_string(self.full_description))
+            .help(get_message_string(self.remediation))
+            .default_configuration(get_configuration(level.to_string()))
+            .build();
+        descriptor.help_uri = self.help_uri.map(str::to_string);
+        descriptor
+    }
+}
+
+const GCC_INSTRUMENTATION: &str =
+    "https://gcc.gnu.org/onlinedocs/gcc/Instrumentation-Options.html";
+const LD_OPTIONS: &str =
+    "https://sourceware.org/binutils/docs/ld/Options.html";
+const LD_SO: &str = "https://man7.org/linux/man-pages/man8/ld.so.8.html";
+const MODULE_SIGNING: &str =
+    "https://docs.kernel.org/admin-guide/module-signing.html";
+const MSVC_LINKER: &str =
+    "https://learn.microsoft.com/en-us/cpp/build/reference/linker-options";
+
+const RULES: &[Rule] = &[
+    // informational properties of all formats
+    Rule {
+        id: "architecture",
+        name: "Architecture",
+        short_description: "Target architecture of the binary",
+        full_description: "The instruction set the binary was built for.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "bitness",
+        name: "Bitness",
+        short_description: "Word size of the binary",
+        full_description: "Whether the binary is a 32-bit or 64-bit image. \
+            64-bit images have more entropy for address space layout \
+            randomization.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "endianness",
+        name: "Endianness",
+        short_description: "Byte order of the binary",
+        full_description: "The byte order of the target architecture.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "dyn_linking",
+        name: "DynamicLinking",
+        short_description: "Binary is dynamically linked",
+        full_description: "Whether the binary loads shared libraries at \
+            runtime. Mitigations of the libraries apply to the process too.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "symbols",
+        name: "Symbols",
+        short_description: "Number of symbols",
+        full_description: "The number of symbols in the symbol table. \
+            Unstripped binaries ease reverse engineering.",
+        remediation: "Strip release builds if symbols are not needed.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "ASan",
+        name: "AddressSanitizer",
+        short_description: "Binary is instrumented with AddressSanitizer",
+        full_description: "AddressSanitizer is a debugging tool. Its runtime \
+            exposes additional attack surface and is not meant for \
+            production builds.",
+        remediation: "Build release binaries without -fsanitize=address.",
+        help_uri: Some("https://clang.llvm.org/docs/AddressSanitizer.html"),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "canary",
+        name: "StackCanary",
+        short_description: "Stack canaries detect stack buffer overflows",
+        full_description: "Functions with stack buffers place a random value \
+            before the return address and check it before returning, which \
+            stops many stack buffer overflow exploits.",
+        remediation: "Compile with -fstack-protector-strong.",
+        help_uri: Some(GCC_INSTRUMENTATION),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "rpath",
+        name: "Rpath",
+        short_description: "Library search path embedded in the binary",
+        full_description: "RPATH directories are searched before the system \
+            library directories. Writable, missing or relative directories \
+            allow an attacker to inject libraries into the process.",
+        remediation: "Remove the RPATH or only use root-owned absolute \
+            directories.",
+        help_uri: Some(LD_SO),
+        level: Level::Warning,
+    },
+    // ELF
+    Rule {
+        id: "interpreter",
+        name: "Interpreter",
+        short_description: "Program interpreter of the binary",
+        full_description: "The dynamic loader which maps the binary and its \
+            libraries.",
+        remediation: "Informational, no action required.",
+        help_uri: Some(LD_SO),
+        level: Level::Note,
+    },
+    Rule {
+        id: "Clang CFI",
+        name: "ClangCfi",
+        short_description: "Clang control flow integrity",
+        full_description: "Clang CFI checks that indirect calls target \
+            functions of the expected type.",
+        remediation: "Compile with -flto -fsanitize=cfi.",
+        help_uri: Some("https://clang.llvm.org/docs/ControlFlowIntegrity.html"),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "Clang SafeStack",
+        name: "ClangSafeStack",
+        short_description: "Clang SafeStack",
+        full_description: "SafeStack moves unsafe stack objects to a separate \
+            stack, protecting return addresses from stack buffer overflows.",
+        remediation: "Compile with -fsanitize=safe-stack.",
+        help_uri: Some("https://clang.llvm.org/docs/SafeStack.html"),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "Stack Clash Protection",
+        name: "StackClashProtection",
+        short_description: "Stack clash protection",
+        full_description: "Large stack allocations are probed page by page, \
+            so the stack can not be moved past the guard page into other \
+            memory.",
+        remediation: "Compile with -fstack-clash-protection.",
+        help_uri: Some(GCC_INSTRUMENTATION),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "IBT",
+        name: "IndirectBranchTracking",
+        short_description: "Intel CET indirect branch tracking",
+        full_description: "Indirect branches must land on ENDBR \
+            instructions, which restricts jump-oriented programming on CPUs \
+            with CET.",
+        remediation: "Compile and link all objects with -fcf-protection=full.",
+        help_uri: Some(GCC_INSTRUMENTATION),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "SHSTK",
+        name: "ShadowStack",
+        short_description: "Intel CET shadow stack",
+        full_description: "Return addresses are checked against a hardware \
+            shadow stack, which stops return-oriented programming on CPUs \
+            with CET.",
+        remediation: "Compile and link all objects with -fcf-protection=full.",
+        help_uri: Some(GCC_INSTRUMENTATION),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "BTI",
+        name: "BranchTargetIdentification",
+        short_description: "AArch64 branch target identification",
+        full_description: "Indirect branches must land on BTI instructions, \
+            which restricts jump-oriented programming.",
+        remediation: "Compile and link all objects with \
+            -mbranch-protection=standard.",
+        help_uri: Some(
+            "https://gcc.gnu.org/onlinedocs/gcc/AArch64-Options.html",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "PAC",
+        name: "PointerAuthentication",
+        short_description: "AArch64 pointer authentication",
+        full_description: "Return addresses are signed and authenticated, \
+            which stops return-oriented programming.",
+        remediation: "Compile and link all objects with \
+            -mbranch-protection=standard.",
+        help_uri: Some(
+            "https://gcc.gnu.org/onlinedocs/gcc/AArch64-Options.html",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "Fortify",
+        name: "Fortify",
+        short_description: "Source fortification",
+        full_description: "_FORTIFY_SOURCE replaces calls to libc functions \
+            with variants that check buffer sizes known at compile time.",
+        remediation: "Compile with optimization and -D_FORTIFY_SOURCE=3.",
+        help_uri: Some(
+            "https://www.gnu.org/software/libc/manual/html_node/Source-Fortification.html",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "fortified functions",
+        name: "FortifiedFunctions",
+        short_description: "Number of fortified functions",
+        full_description: "The number of calls to checked *_chk variants of \
+            libc functions.",
+        remediation: "Compile with optimization and -D_FORTIFY_SOURCE=3.",
+        help_uri: Some(
+            "https://www.gnu.org/software/libc/manual/html_node/Source-Fortification.html",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "fortifiable functions",
+        name: "FortifiableFunctions",
+        short_description: "Number of fortifiable functions",
+        full_description: "The number of calls to libc functions which have \
+            a checked *_chk variant.",
+        remediation: "Compile with optimization and -D_FORTIFY_SOURCE=3.",
+        help_uri: Some(
+            "https://www.gnu.org/software/libc/manual/html_node/Source-Fortification.html",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "NX",
+        name: "NonExecutableStack",
+        short_description: "Non-executable stack",
+        full_description: "A non-executable stack prevents running injected \
+            code from stack buffers. Writable and executable segments \
+            disable NX as well.",
+        remediation: "Link with -z noexecstack and avoid executable stack \
+            requirements of assembly objects and writable code segments.",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "rwx_segments",
+        name: "RwxSegments",
+        short_description: "Writable and executable segments",
+        full_description: "Segments which are both writable and executable \
+            allow injected code to be run directly.",
+        remediation: "Fix linker scripts or flags which merge code and data, \
+            e.g. remove -z execstack and -N/--omagic.",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Error,
+    },
+    Rule {
+        id: "textrel",
+        name: "TextRelocations",
+        short_description: "Text relocations",
+        full_description: "Text relocations require the loader to make code \
+            pages writable, which defeats W^X.",
+        remediation: "Compile all objects as position independent code \
+            (-fPIC).",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Error,
+    },
+    Rule {
+        id: "PIE",
+        name: "PositionIndependentExecutable",
+        short_description: "Position independent executable",
+        full_description: "Position independent executables are loaded at a \
+            random address, which makes code reuse attacks harder.",
+        remediation: "Compile with -fPIE and link with -pie.",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "relro",
+        name: "Relro",
+        short_description: "Relocation read-only",
+        full_description: "RELRO makes relocated data read-only after \
+            loading. Full RELRO also resolves all symbols at startup so the \
+            GOT can be protected.",
+        remediation: "Link with -z relro -z now.",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "runpath",
+        name: "Runpath",
+        short_description: "Library search path embedded in the binary",
+        full_description: "RUNPATH directories are searched for libraries. \
+            Writable, missing or relative directories allow an attacker to \
+            inject libraries into the process.",
+        remediation: "Remove the RUNPATH or only use root-owned absolute \
+            directories.",
+        help_uri: Some(LD_SO),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "dynlibs",
+        name: "DynamicLibraries",
+        short_description: "Needed shared libraries",
+        full_description: "The shared libraries listed as DT_NEEDED.",
+        remediation: "Informational, no action required.",
+        help_uri: Some(LD_SO),
+        level: Level::Note,
+    },
+    Rule {
+        id: "seperate_code",
+        name: "SeparateCode",
+        short_description: "Code and data in separate segments",
+        full_description: "Without separate code segments read-only data is \
+            mapped executable, which provides additional gadgets for code \
+            reuse attacks.",
+        remediation: "Link with -z separate-code.",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Warning,
+    },
+    // Linux kernel modules
+    Rule {
+        id: "module_signature",
+        name: "ModuleSignature",
+        short_description: "Kernel module is signed",
+        full_description: "Kernels enforcing module signatures refuse to \
+            load unsigned modules, and load them while tainting the kernel \
+            otherwise.",
+        remediation: "Sign the module with scripts/sign-file or build it \
+            with CONFIG_MODULE_SIG_ALL.",
+        help_uri: Some(MODULE_SIGNING),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "retpoline",
+        name: "Retpoline",
+        short_description: "Kernel module is built with retpolines",
+        full_description: "Retpolines replace indirect branches to mitigate \
+            Spectre variant 2 branch target injection.",
+        remediation: "Build the module against a kernel configured with \
+            CONFIG_MITIGATION_RETPOLINE.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "vermagic",
+        name: "Vermagic",
+        short_description: "Kernel version magic",
+        full_description: "The kernel release and configuration the module \
+            was built for. Modules with a different vermagic are rejected.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "module_license",
+        name: "ModuleLicense",
+        short_description: "Kernel module license",
+        full_description: "Modules without a GPL compatible license taint \
+            the kernel and can not use GPL-only symbols.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    // PE
+    Rule {
+        id: "aslr",
+        name: "Aslr",
+        short_description: "Address space layout randomization",
+        full_description: "Images with a dynamic base, ideally with high \
+            entropy, are loaded at random addresses.",
+        remediation: "Link with /DYNAMICBASE and /HIGHENTROPYVA.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "authenticode",
+        name: "Authenticode",
+        short_description: "Authenticode signature",
+        full_description: "Signed images allow verifying their publisher and \
+            integrity.",
+        remediation: "Sign the image with signtool.",
+        help_uri: Some(
+            "https://learn.microsoft.com/en-us/windows-hardware/drivers/install/authenticode",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "cfg",
+        name: "ControlFlowGuard",
+        short_description: "Control flow guard",
+        full_description: "Control flow guard checks indirect call targets \
+            against a table of valid functions.",
+        remediation: "Compile and link with /guard:cf.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "dotnet",
+        name: "DotNet",
+        short_description: ".NET assembly",
+        full_description: "Whether the image is a managed .NET assembly, \
+            which native mitigations only partially apply to.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "nx",
+        name: "DataExecutionPrevention",
+        short_description: "Data execution prevention",
+        full_description: "NX compatible images run with data execution \
+            prevention, which stops executing injected data.",
+        remediation: "Link with /NXCOMPAT.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "force integrity",
+        name: "ForceIntegrity",
+        short_description: "Signature check at load time",
+        full_description: "The loader requires a valid signature before \
+            mapping the image.",
+        remediation: "Link with /INTEGRITYCHECK and sign the image.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "GS",
+        name: "BufferSecurityCheck",
+        short_description: "Buffer security check",
+        full_description: "/GS places a cookie before the return address and \
+            checks it before returning, which stops many stack buffer \
+            overflow exploits.",
+        remediation: "Compile with /GS.",
+        help_uri: Some(
+            "https://learn.microsoft.com/en-us/cpp/build/reference/gs-buffer-security-check",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "isolation",
+        name: "Isolation",
+        short_description: "Manifest isolation",
+        full_description: "The loader looks up a manifest for the image, \
+            which prevents some DLL planting attacks.",
+        remediation: "Do not link with /ALLOWISOLATION:NO.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "rfg",
+        name: "ReturnFlowGuard",
+        short_description: "Return flow guard",
+        full_description: "Return flow guard protects return addresses with \
+            a shadow stack.",
+        remediation: "Prefer CET shadow stack compatibility (/CETCOMPAT), \
+            return flow guard is deprecated.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "safeseh",
+        name: "SafeSeh",
+        short_description: "Safe structured exception handlers",
+        full_description: "Only registered exception handlers can be called, \
+            which stops SEH overwrite exploits on 32-bit images.",
+        remediation: "Link with /SAFESEH.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "seh",
+        name: "StructuredExceptionHandling",
+        short_description: "Structured exception handling",
+        full_description: "Whether the image uses structured exception \
+            handling.",
+        remediation: "Use SafeSEH for images with exception handlers.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "cet",
+        name: "CetCompatible",
+        short_description: "CET shadow stack compatibility",
+        full_description: "CET compatible images run with a hardware shadow \
+            stack, which stops return-oriented programming.",
+        remediation: "Link with /CETCOMPAT.",
+        help_uri: Some(MSVC_LINKER),
+        level: Level::Warning,
+    },
+    // Mach-O
+    Rule {
+        id: "arc",
+        name: "AutomaticReferenceCounting",
+        short_description: "Automatic reference counting",
+        full_description: "ARC manages Objective-C object lifetimes, which \
+            prevents many use-after-free bugs.",
+        remediation: "Compile Objective-C code with -fobjc-arc.",
+        help_uri: Some(
+            "https://clang.llvm.org/docs/AutomaticReferenceCounting.html",
+        ),
+        level: Level::Warning,
+    },
+    Rule {
+        id: "encrypted",
+        name: "Encrypted",
+        short_description: "Encrypted segments",
+        full_description: "Whether the binary contains encrypted segments.",
+        remediation: "Informational, no action required.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "fortify",
+        name: "Fortify",
+        short_description: "Source fortification",
+        full_description: "_FORTIFY_SOURCE replaces calls to libc functions \
+            with variants that check buffer sizes known at compile time.",
+        remediation: "Compile with optimization and -D_FORTIFY_SOURCE=2.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "fortifed",
+        name: "FortifiedFunctions",
+        short_description: "Number of fortified functions",
+        full_description: "The number of calls to checked *_chk variants of \
+            libc functions.",
+        remediation: "Compile with optimization and -D_FORTIFY_SOURCE=2.",
+        help_uri: None,
+        level: Level::Note,
+    },
+    Rule {
+        id: "nx heap",
+        name: "NonExecutableHeap",
+        short_description: "Non-executable heap",
+        full_description: "A non-executable heap prevents running injected \
+            code from heap buffers.",
+        remediation: "Do not link with -allow_heap_execute.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "nx stack",
+        name: "NonExecutableStack",
+        short_description: "Non-executable stack",
+        full_description: "A non-executable stack prevents running injected \
+            code from stack buffers.",
+        remediation: "Do not link with -allow_stack_execute.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "pie",
+        name: "PositionIndependentExecutable",
+        short_description: "Position independent executable",
+        full_description: "Position independent executables are loaded at a \
+            random address, which makes code reuse attacks harder.",
+        remediation: "Link with -pie.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "restrict",
+        name: "Restrict",
+        short_description: "Restrict segment",
+        full_description: "A __RESTRICT segment makes dyld ignore DYLD_* \
+            environment variables, which prevents library injection.",
+        remediation: "Link with -sectcreate __RESTRICT __restrict /dev/null \
+            or use the hardened runtime.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    // running processes
+    Rule {
+        id: "stale_image",
+        name: "StaleImage",
+        short_description: "Deleted or replaced running image",
+        full_description: "The process runs an executable or library which \
+            was deleted or replaced on disk since it was loaded, e.g. by a \
+            package upgrade, so security fixes of the update are not in \
+            effect yet.",
+        remediation: "Restart the process.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    // process memory mappings
+    Rule {
+        id: "rwx_mappings",
+        name: "RwxMappings",
+        short_description: "Writable and executable memory",
+        full_description: "Memory which is writable and executable at the \
+            same time lets injected code run without changing the \
+            protection.",
+        remediation: "Map code writable and executable in turns, e.g. by \
+            mapping JIT code twice or switching the protection with \
+            mprotect/VirtualProtect.",
+        help_uri: None,
+        level: Level::Error,
+    },
+    Rule {
+        id: "anonymous_exec_mappings",
+        name: "AnonymousExecutableMappings",
+        short_description: "Anonymous executable memory",
+        full_description: "Executable memory not backed by a file is \
+            generated at runtime by JIT compilers, or injected code.",
+        remediation: "Verify the process is expected to generate code at \
+            runtime.",
+        help_uri: None,
+        level: Level::Warning,
+    },
+    Rule {
+        id: "exec_stack_mapping",
+        name: "ExecutableStackMapping",
+        short_description: "Executable stack at runtime",
+        full_description: "An executable stack of the running process lets \
+            injected code run from stack buffers, e.g. because a loaded \
+            library requires an executable stack.",
+        remediation: "Rebuild the binary and all libraries with \
+            -z noexecstack.",
+        help_uri: Some(LD_OPTIONS),
+        level: Level::Error,
+    },
+    Rule {
+        id: "exec_heap_mapping",
+        name: "ExecutableHeapMapping",
+        short_description: "Executable heap at runtime",
+        full_description: "An executable heap of the running process lets \
+            injected code run from heap buffers, e.g. because of the \
+            READ_IMPLIES_EXEC personality.",
+        remediation: "Rebuild the binary with a non-executable stack and \
+            do not create executable heaps.",
+        help_uri: None,
+        level: Level::Error,
+    },
+    Rule {
+        id: "writable_file_exec_mappings",
+        name: "WritableFileExecutableMappings",
+        short_description: "Writable executable file mappings",
+        full_description: "Executable mappings of files which are also \
+            writable allow changing the code of the process, e.g. text \
+            relocations of a library.",
+        remediation: "Build libraries as position independent code without \
+            text relocations.",
+        help_uri: None,
+        level: Level::Error,
+    },
+];
@@ -0,0 +1,198 @@
+//! Severity profiles for SARIF reports
+//!
+//! A profile decides the level of failed checks, i.e. results which are a
+//! `warning` or `error` by default. Passing checks and informational notes
+//! keep their level, unless the profile has a level for their rule. Failed
+//! checks which do not apply to the target of a binary, e.g. `SafeSEH` of
+//! 64-bit images, are notes unless the profile has a level for their rule.
+//! The built-in profiles are `default`, `strict` (every failed check is an
+//! error) and `informational` (every failed check is a note). Custom
+//! profiles use the rule ids of the SARIF report:
+//!
+//! ```toml
+//! # level of failed checks without a rule below, the default if omitted
+//! level = "warning"
+//!
+//! [rules]
+//! arc = "note"
+//! rwx_segments = "error"
+//! ```
+use crate::sarif as sarif_rules;
+use serde::{Deserialize, Serialize};
+use serde_sarif::sarif;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::{fmt, fs, io};
+
+/// Severity level of a SARIF result
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    None,
+    Note,
+    Warning,
+    Error,
+}
+
+impl From<Level> for sarif::ResultLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::None => Self::None,
+            Level::Note => Self::Note,
+            Level::Warning => Self::Warning,
+            Level::Error => Self::Error,
+        }
+    }
+}
+
+impl From<sarif::ResultLevel> for Level {
+    fn from(level: sarif::ResultLevel) -> Self {
+        match level {
+            sarif::ResultLevel::None => Self::None,
+            sarif::ResultLevel::Note => Self::Note,
+            sarif::ResultLevel::Warning => Self::Warning,
+            sarif::ResultLevel::Error => Self::Error,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Note => write!(f, "note"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Levels of failed checks
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SeverityProfile {
+    /// Level of failed checks without a rule specific level, `None` keeps
+    /// the built-in level
+    #[serde(default)]
+    pub level: Option<Level>,
+    /// Level of failed checks per rule id
+    #[serde(default)]
+    pub rules: BTreeMap<String, Level>,
+}
+
+#[derive(Debug)]
+pub enum SeverityError {
+    /// I/O error
+    IO(io::Error),
+    /// Invalid TOML profile
+    Toml(toml::de::Error),
+    /// Invalid JSON profile
+    Json(serde_json::Error),
+    /// Rule id which is neither a check of binaries nor of the kernel
+    UnknownRule(String),
+}
+
+impl fmt::Display for SeverityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IO(err) => err.fmt(f),
+            Self::Toml(err) => {
+                write!(f, "Invalid TOML severity profile: {err}")
+            }
+            Self::Json(err) => {
+                write!(f, "Invalid JSON severity profile: {err}")
+            }
+            Self::UnknownRule(rule) => {
+                write!(f, "Unknown rule in severity profile: {rule}")
+            }
+        }
+    }
+}
+
+impl From<io::Error> for SeverityError {
+    fn from(e: io::Error) -> Self {
+        SeverityError::IO(e)
+    }
+}
+
+impl SeverityProfile {
+    /// Get a built-in profile by name.
+    #[must_use]
+    pub fn builtin(name: &str) -> Option<Self> {
+        let level = match name {
+            "default" => None,
+            "strict" => Some(Level::Error),
+            "informational" => Some(Level::Note),
+            _ => return None,
+        };
+        Some(Self { level, rules: BTreeMap::new() })
+    }
+
+    /// Parse a profile in TOML or, if it starts with `{`, JSON format.
+    ///
+    /// # Errors
+    /// Will fail if the profile is malformed or has unknown levels or rules.
+    pub fn parse(content: &str) -> Result<Self, SeverityError> {
+        let profile: Self = if content.trim_start().starts_with('{') {
+            serde_json::from_str(content).map_err(SeverityError::Json)?
+        } else {
+            toml::from_str(content).map_err(SeverityError::Toml)?
+        };
+        if let Some(rule) =
+            profile.rules.keys().find(|rule| !sarif_rules::is_rule(rule))
+        {
+            return Err(SeverityError::UnknownRule(rule.clone()));
+        }
+        Ok(profile)
+    }
+
+    /// Read and parse a profile file, see `SeverityProfile::parse`.
+    ///
+    /// # Errors
+    /// Will fail if the file can not be read or the profile is malformed.
+    pub fn from_file(path: &Path) -> Result<Self, SeverityError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Level of a result of `rule_id` with the built-in `level`.
+    #[must_use]
+    pub fn level(
+        &self,
+        rule_id: &str,
+        level: sarif::ResultLevel,
+    ) -> sarif::ResultLevel {
+        if matches!(level, sarif::ResultLevel::None) {
+            return level;
+        }
+        if let Some(rule_level) = self.rules.get(rule_id) {
+            return (*rule_level).into();
+        }
+        match (level, self.level) {
+            (
+                sarif::ResultLevel::Warning | sarif::ResultLevel::Error,
+                Some(level),
+            ) => level.into(),
+            _ => level,
+        }
+    }
+
+    /// Level of a result of `rule_id` with the built-in `level` for a check
+    /// which does not apply to the target of the binary.
+    #[must_use]
+    pub fn inapplicable_level(
+        &self,
+        rule_id: &str,
+        level: sarif::ResultLevel,
+    ) -> sarif::ResultLevel {
+        if matches!(level, sarif::ResultLevel::None) {
+            return level;
+        }
+        self.rules
+            .get(rule_id)
+            .map_or(sarif::ResultLevel::Note, |rule_level| {
+                (*rule_level).into()
+            })
+    }
+}
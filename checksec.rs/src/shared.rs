@@ -2,8 +2,16 @@
 #[cfg(feature = "color")]
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::fmt;
+use std::fs;
 use std::ops::Deref;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Component, Path, PathBuf};
+
+// same limit as the kernel's path resolution
+const MAX_SYMLINK_HOPS: usize = 40;
 
 /// Describe the endianness of the binary
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
@@ -26,7 +34,69 @@ impl fmt::Display for Endianness {
 pub enum Rpath {
     None,
     Yes(String),
+    /// Directory is writable by users other than its owner
     YesRW(String),
+    /// Directory does not exist
+    Missing(String),
+    /// Directory is relative to the working directory
+    Relative(String),
+}
+
+impl Rpath {
+    /// Classify a search path `entry` by the state of its directory `dir`
+    /// after expansion of `$ORIGIN`/`@loader_path` style tokens. `dir` is
+    /// resolved inside `sysroot`, entries which can not be expanded are
+    /// skipped by the loader and kept as `Rpath::Yes`.
+    #[must_use]
+    pub fn check_dir(entry: &str, dir: Option<&Path>, sysroot: &Path) -> Self {
+        let Some(dir) = dir else {
+            return Rpath::Yes(entry.into());
+        };
+        if dir.is_relative() {
+            return Rpath::Relative(entry.into());
+        }
+        match resolve_existing(sysroot, dir).map(fs::metadata) {
+            Some(Ok(meta)) if meta.is_dir() => {
+                if is_writable_by_others(&meta) {
+                    Rpath::YesRW(entry.into())
+                } else {
+                    Rpath::Yes(entry.into())
+                }
+            }
+            _ => Rpath::Missing(entry.into()),
+        }
+    }
+
+    /// Search path entry, `None` for `Rpath::None`
+    #[must_use]
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Rpath::None => None,
+            Rpath::Yes(path)
+            | Rpath::YesRW(path)
+            | Rpath::Missing(path)
+            | Rpath::Relative(path) => Some(path),
+        }
+    }
+
+    /// Whether the entry allows planting libraries, see `Rpath::check_dir`
+    #[must_use]
+    pub fn is_insecure(&self) -> bool {
+        matches!(
+            self,
+            Rpath::YesRW(_) | Rpath::Missing(_) | Rpath::Relative(_)
+        )
+    }
+}
+
+#[cfg(unix)]
+fn is_writable_by_others(meta: &fs::Metadata) -> bool {
+    meta.permissions().mode() & 0o022 != 0
+}
+
+#[cfg(not(unix))]
+fn is_writable_by_others(_meta: &fs::Metadata) -> bool {
+    false
 }
 /// wrapper for Vec<Rpath> to allow easy color output per path entry
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
@@ -38,6 +108,31 @@ impl VecRpath {
     pub fn new(v: Vec<Rpath>) -> Self {
         Self { paths: v }
     }
+
+    /// Check the directory of every entry on disk, see `Rpath::check_dir`.
+    /// `expand` maps an entry to its directory.
+    #[must_use]
+    pub fn check_dirs<F>(&self, sysroot: &Path, expand: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        Self::new(
+            self.paths
+                .iter()
+                .map(|rpath| match rpath {
+                    Rpath::None => Rpath::None,
+                    Rpath::Yes(entry)
+                    | Rpath::YesRW(entry)
+                    | Rpath::Missing(entry)
+                    | Rpath::Relative(entry) => Rpath::check_dir(
+                        entry,
+                        expand(entry).as_deref().map(Path::new),
+                        sysroot,
+                    ),
+                })
+                .collect(),
+        )
+    }
 }
 impl Deref for VecRpath {
     type Target = Vec<Rpath>;
@@ -51,7 +146,10 @@ impl fmt::Display for VecRpath {
         let mut s: Vec<String> = Vec::<String>::new();
         for v in &self.paths {
             match v {
-                Rpath::Yes(p) | Rpath::YesRW(p) => s.push(p.to_string()),
+                Rpath::Yes(p)
+                | Rpath::YesRW(p)
+                | Rpath::Missing(p)
+                | Rpath::Relative(p) => s.push(p.to_string()),
                 Rpath::None => s.push("None".to_string()),
             }
         }
@@ -64,10 +162,99 @@ impl fmt::Display for VecRpath {
         let mut s: Vec<String> = Vec::<String>::new();
         for v in &self.paths {
             match v {
-                Rpath::Yes(p) | Rpath::YesRW(p) => s.push(p.red().to_string()),
+                Rpath::Yes(p) => s.push(p.red().to_string()),
+                Rpath::YesRW(p) | Rpath::Missing(p) | Rpath::Relative(p) => {
+                    s.push(p.red().bold().to_string());
+                }
                 Rpath::None => s.push("None".green().to_string()),
             }
         }
         write!(f, "{}", s.join(":"))
     }
 }
+
+/// Absolute directory of the binary at `binarypath` as seen inside
+/// `sysroot`, the value of `$ORIGIN`/`@loader_path`.
+#[must_use]
+pub fn binary_dir_in_sysroot(
+    binarypath: &Path,
+    sysroot: &Path,
+) -> Option<PathBuf> {
+    let parent = binarypath.parent()?;
+    let parent =
+        if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+    match parent.strip_prefix(sysroot) {
+        Ok(inner) => Some(Path::new("/").join(inner)),
+        Err(_) => std::path::absolute(parent).ok(),
+    }
+}
+
+/// Join an absolute `path` below `sysroot`.
+#[must_use]
+pub fn sysroot_join(sysroot: &Path, path: &Path) -> PathBuf {
+    sysroot.join(path.strip_prefix("/").unwrap_or(path))
+}
+
+/// Resolve an absolute `path` as seen from inside `sysroot` to a regular file
+/// on the host.
+///
+/// Symbolic links are followed relative to `sysroot`, so absolute link targets
+/// of an extracted root filesystem do not escape to the host. Relative paths
+/// are resolved against the current directory, like the dynamic loader does.
+#[must_use]
+pub fn resolve_in_sysroot(sysroot: &Path, path: &Path) -> Option<PathBuf> {
+    resolve_existing(sysroot, path).filter(|path| path.is_file())
+}
+
+/// Resolve `path` inside `sysroot` like `resolve_in_sysroot`, to an existing
+/// host path of any type.
+#[must_use]
+pub fn resolve_existing(sysroot: &Path, path: &Path) -> Option<PathBuf> {
+    if sysroot == Path::new("/") || path.is_relative() {
+        return path.exists().then(|| path.to_path_buf());
+    }
+
+    let mut pending: Vec<OsString> = Vec::new();
+    push_components(&mut pending, path);
+    let mut resolved = PathBuf::from("/");
+    let mut hops = 0;
+    while let Some(component) = pending.pop() {
+        if component == ".." {
+            resolved.pop();
+            continue;
+        }
+        let candidate = resolved.join(&component);
+        let host = sysroot_join(sysroot, &candidate);
+        if fs::symlink_metadata(&host).ok()?.file_type().is_symlink() {
+            hops += 1;
+            if hops > MAX_SYMLINK_HOPS {
+                return None;
+            }
+            let target = fs::read_link(&host).ok()?;
+            if target.is_absolute() {
+                resolved = PathBuf::from("/");
+            }
+            push_components(&mut pending, &target);
+        } else {
+            resolved = candidate;
+        }
+    }
+
+    Some(sysroot_join(sysroot, &resolved))
+}
+
+// Push the components of `path` in reverse order, so they can be popped
+// front to back.
+fn push_components(pending: &mut Vec<OsString>, path: &Path) {
+    pending.extend(
+        path.components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_os_string()),
+                Component::ParentDir => Some(OsString::from("..")),
+                Component::RootDir
+                | Component::CurDir
+                | Component::Prefix(_) => None,
+            })
+            .rev(),
+    );
+}
@@ -0,0 +1,11 @@
+gcc -O2 -nostdlib -static -o cet_full src/nostartfiles.c -fcf-protection=full
+gcc -O2 -nostdlib -static -o cet_ibt src/nostartfiles.c -fcf-protection=branch
+gcc -O2 -nostdlib -static -o cet_shstk src/nostartfiles.c -fcf-protection=return
+python3 src/gnu_property_aarch64.py aarch64_bti_pac 0x3
+python3 src/gnu_property_aarch64.py aarch64_bti 0x1
+gcc -O2 -nostdlib -static -o rwx_segment src/nostartfiles.c -Wl,--omagic
+gcc -nostdlib -shared -o textrel_dso src/textrel.s
+gcc -O2 -c -o kmod.ko src/kmod.c -DRETPOLINE -fstack-protector-all -fcf-protection=branch -mindirect-branch=thunk-extern
+cp kmod.ko kmod_signed.ko && printf '\0\0\0\0\0\0\0\0\0\0\0\0~Module signature appended~\n' >> kmod_signed.ko
+gcc -O2 -c -o kmod_none.ko src/kmod.c -fno-stack-protector -fcf-protection=none
+gcc -O2 -c -o kmod_partial_ibt.ko src/kmod.c -DPARTIAL_IBT -fcf-protection=branch
@@ -0,0 +1,48 @@
+#!/usr/bin/env python3
+"""Emit a minimal AArch64 ELF64 whose only content is a GNU property note.
+
+usage: gnu_property_aarch64.py <output> <GNU_PROPERTY_AARCH64_FEATURE_1_AND>
+"""
+import struct
+import sys
+
+EM_AARCH64 = 183
+PT_NOTE = 4
+PT_GNU_PROPERTY = 0x6474E553
+NT_GNU_PROPERTY_TYPE_0 = 5
+GNU_PROPERTY_AARCH64_FEATURE_1_AND = 0xC0000000
+
+out, features = sys.argv[1], int(sys.argv[2], 0)
+
+desc = struct.pack("<III", GNU_PROPERTY_AARCH64_FEATURE_1_AND, 4, features)
+desc += b"\0" * (-len(desc) % 8)
+note = struct.pack("<III", 4, len(desc), NT_GNU_PROPERTY_TYPE_0) + b"GNU\0" + desc
+
+ehsize, phentsize, phnum = 64, 56, 2
+note_off = ehsize + phentsize * phnum
+
+ehdr = b"\x7fELF" + bytes([2, 1, 1, 0]) + b"\0" * 8
+ehdr += struct.pack(
+    "<HHIQQQIHHHHHH",
+    2,  # ET_EXEC
+    EM_AARCH64,
+    1,  # EV_CURRENT
+    0x400000,  # e_entry
+    ehsize,  # e_phoff
+    0,  # e_shoff
+    0,  # e_flags
+    ehsize,
+    phentsize,
+    phnum,
+    0,  # e_shentsize
+    0,  # e_shnum
+    0,  # e_shstrndx
+)
+phdrs = b"".join(
+    struct.pack("<IIQQQQQQ", p_type, 4, note_off, 0x400000 + note_off,
+                0x400000 + note_off, len(note), len(note), 8)
+    for p_type in (PT_NOTE, PT_GNU_PROPERTY)
+)
+
+with open(out, "wb") as f:
+    f.write(ehdr + phdrs + note)
@@ -0,0 +1,48 @@
+/*
+ * Stand-in for an out-of-tree Linux kernel module, built without kernel
+ * headers. It has the `.modinfo` and `.gnu.linkonce.this_module` sections
+ * kbuild creates for a module.
+ */
+#define MODULE_INFO(tag, info)                                     \
+    static const char __modinfo_##tag[]                            \
+        __attribute__((section(".modinfo"), used, aligned(1))) =   \
+            #tag "=" info
+
+MODULE_INFO(license, "GPL");
+MODULE_INFO(vermagic, "6.8.0-45-generic SMP preempt mod_unload modversions ");
+#ifdef RETPOLINE
+MODULE_INFO(retpoline, "Y");
+#endif
+MODULE_INFO(name, "kmod");
+
+struct module {
+    char name[56];
+};
+
+__attribute__((section(".gnu.linkonce.this_module")))
+struct module __this_module = {.name = "kmod"};
+
+#ifdef PARTIAL_IBT
+/* only init_module starts with ENDBR */
+#define NOCF_CHECK __attribute__((nocf_check))
+#else
+#define NOCF_CHECK
+#endif
+
+extern void consume(char *buf);
+extern void (*handler)(void);
+
+int init_module(void) {
+    char buf[64];
+    consume(buf);
+    handler();
+    return 0;
+}
+
+NOCF_CHECK void cleanup_module(void) {}
+
+#ifdef PARTIAL_IBT
+NOCF_CHECK void kmod_open(void) { handler(); }
+NOCF_CHECK void kmod_release(void) { handler(); }
+NOCF_CHECK void kmod_ioctl(void) { handler(); }
+#endif
@@ -0,0 +1,5 @@
+// Minimal freestanding program, linked without crt files so the GNU property
+// notes of the output only reflect the flags used to build this file.
+void _start(void) {
+    __asm__ volatile("mov $60, %eax\n xor %edi, %edi\n syscall");
+}
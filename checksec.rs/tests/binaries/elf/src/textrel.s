@@ -0,0 +1,12 @@
+# Absolute address stored in .text, forcing the linker to emit a dynamic
+# relocation against a read-only segment (DT_TEXTREL / DF_TEXTREL).
+    .text
+    .globl bump
+bump:
+    .quad counter
+    ret
+
+    .data
+    .globl counter
+counter:
+    .long 0
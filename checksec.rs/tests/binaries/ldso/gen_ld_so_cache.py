@@ -0,0 +1,56 @@
+#!/usr/bin/env python3
+# Generate ld.so.cache fixtures in the old, new and combined formats.
+import struct
+
+ENTRIES = [
+    # flags, soname, path
+    (0x0303, "libfoo.so.1", "/usr/lib/x86_64-linux-gnu/libfoo.so.1"),
+    (0x0003, "libfoo.so.1", "/usr/lib/i386-linux-gnu/libfoo.so.1"),
+    (0x0a03, "libfoo.so.1", "/usr/lib/aarch64-linux-gnu/libfoo.so.1"),
+    (0x0001, "libbar.so.2", "/usr/lib/libbar.so.2"),
+]
+
+
+def strings(base):
+    table = b""
+    offsets = []
+    for _, soname, path in ENTRIES:
+        key = base + len(table)
+        table += soname.encode() + b"\0"
+        value = base + len(table)
+        table += path.encode() + b"\0"
+        offsets.append((key, value))
+    return table, offsets
+
+
+def old_format():
+    table, offsets = strings(0)
+    out = b"ld.so-1.7.0\0" + struct.pack("<I", len(ENTRIES))
+    for (flags, _, _), (key, value) in zip(ENTRIES, offsets):
+        out += struct.pack("<iII", flags, key, value)
+    return out + table
+
+
+def new_format():
+    base = 48 + 24 * len(ENTRIES)
+    table, offsets = strings(base)
+    out = b"glibc-ld.so.cache1.1"
+    out += struct.pack("<IIB3xI12x", len(ENTRIES), len(table), 2, 0)
+    for (flags, _, _), (key, value) in zip(ENTRIES, offsets):
+        out += struct.pack("<iIIIQ", flags, key, value, 0, 0)
+    return out + table
+
+
+def compat_format():
+    # old format without entries, followed by the 8 byte aligned new format
+    out = b"ld.so-1.7.0\0" + struct.pack("<I", 0)
+    return out + b"\0" * (-len(out) % 8) + new_format()
+
+
+for name, data in [
+    ("ld.so.cache-old", old_format()),
+    ("ld.so.cache-new", new_format()),
+    ("ld.so.cache-compat", compat_format()),
+]:
+    with open(name, "wb") as f:
+        f.write(data)
@@ -0,0 +1,34 @@
+#!/bin/sh
+# Generate a minimal root filesystem to test library lookup with --sysroot.
+set -e
+rm -rf sysroot
+mkdir -p sysroot/etc/ld.so.conf.d sysroot/opt/lib sysroot/usr/lib \
+    sysroot/usr/bin sysroot/lib
+echo "include /etc/ld.so.conf.d/*.conf" > sysroot/etc/ld.so.conf
+echo "/opt/lib" > sysroot/etc/ld.so.conf.d/opt.conf
+gcc -nostdlib -shared -s -Wl,-soname,libconf.so.1 \
+    -o sysroot/opt/lib/libconf.so.1.0 ../elf/src/nostartfiles.c
+gcc -nostdlib -shared -s -Wl,-soname,librpath.so.1 \
+    -o sysroot/usr/lib/librpath.so.1 ../elf/src/nostartfiles.c
+# absolute links must not escape the sysroot
+ln -s /opt/lib/libconf.so.1.0 sysroot/opt/lib/libconf.so.1
+ln -s ../opt/lib/libconf.so.1.0 sysroot/lib/ld-test.so.1
+gcc -nostdlib -s -o sysroot/usr/bin/app ../elf/src/nostartfiles.c \
+    -Wl,--no-as-needed sysroot/opt/lib/libconf.so.1.0 sysroot/usr/lib/librpath.so.1 \
+    -Wl,--disable-new-dtags -Wl,-rpath,'$ORIGIN/../lib' \
+    -Wl,--dynamic-linker=/lib/ld-test.so.1
+# RPATH with dynamic string tokens, relative and missing directories
+gcc -nostdlib -s -o sysroot/usr/bin/app-dst ../elf/src/nostartfiles.c \
+    -Wl,--no-as-needed sysroot/usr/lib/librpath.so.1 \
+    -Wl,--disable-new-dtags \
+    -Wl,-rpath,'${ORIGIN}/../lib:$ORIGIN/missing:relative:/$LIB:$ORIGINAL'
+# RUNPATH searched before the default directories, skipping a library of
+# another ELF class
+mkdir -p sysroot/opt/runpath sysroot/opt/lib32
+gcc -nostdlib -shared -s -Wl,-soname,librpath.so.1 \
+    -o sysroot/opt/runpath/librpath.so.1 ../elf/src/nostartfiles.c
+gcc -m32 -nostdlib -shared -s -Wl,-soname,librpath.so.1 \
+    -o sysroot/opt/lib32/librpath.so.1 ../elf/src/nostartfiles.c
+gcc -nostdlib -s -o sysroot/usr/bin/app-runpath ../elf/src/nostartfiles.c \
+    -Wl,--no-as-needed sysroot/usr/lib/librpath.so.1 \
+    -Wl,--enable-new-dtags -Wl,-rpath,'/opt/lib32:/opt/runpath'
@@ -0,0 +1,57 @@
+use serde_json::Value;
+use std::process::{Command, Output};
+
+const LEFT: &str = "./tests/binaries/elf/all";
+const RIGHT: &str = "./tests/binaries/elf/none";
+
+fn checksec(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_checksec"))
+        .args(args)
+        .output()
+        .expect("checksec not run")
+}
+
+#[test]
+fn test_compare_output_options() {
+    // global output options are accepted before and after the subcommand
+    for args in [
+        ["--json", "compare", LEFT, RIGHT],
+        ["compare", "--json", LEFT, RIGHT],
+    ] {
+        let output = checksec(&args);
+        assert!(output.status.success(), "{args:?}");
+        let comparison: Value = serde_json::from_slice(&output.stdout)
+            .expect("invalid JSON output");
+        assert_eq!(comparison["left"], LEFT, "{args:?}");
+    }
+    for args in [
+        ["--no-color", "compare", LEFT, RIGHT],
+        ["compare", "--no-color", LEFT, RIGHT],
+    ] {
+        assert!(checksec(&args).status.success(), "{args:?}");
+    }
+}
+
+#[test]
+fn test_compare_scan_options() {
+    // scan options do not apply to the compared binaries
+    for args in [
+        ["-f", LEFT, "compare", LEFT, RIGHT],
+        ["--policy", "policy.toml", "compare", LEFT, RIGHT],
+    ] {
+        let output = checksec(&args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(String::from_utf8_lossy(&output.stderr)
+            .contains("the subcommand 'compare' cannot be used with"));
+    }
+    assert!(!checksec(&["compare", LEFT, RIGHT, "-f", LEFT]).status.success());
+}
+
+#[test]
+fn test_group_by_sarif() {
+    // SARIF results refer to files and can not be grouped
+    let output = checksec(&["--process-all", "--group-by", "unit", "--sarif"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("cannot be used with"));
+}
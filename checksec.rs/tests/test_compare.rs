@@ -0,0 +1,95 @@
+use checksec::{checksec, compare::Comparison};
+use serde_json::json;
+mod utils;
+use utils::file_to_buf;
+
+fn scan(filename: &str) -> checksec::binary::Binary {
+    let filename = filename.to_string();
+    checksec(&file_to_buf(&filename), filename)
+}
+
+#[test]
+fn test_compare_equal() {
+    let comparison = Comparison::new(
+        &scan("./tests/binaries/elf/all"),
+        &scan("./tests/binaries/elf/all"),
+    );
+    assert!(comparison.is_equal());
+    assert_eq!(comparison.blobs.len(), 1);
+}
+
+#[test]
+fn test_compare_fields() {
+    let comparison = Comparison::new(
+        &scan("./tests/binaries/elf/all"),
+        &scan("./tests/binaries/elf/none"),
+    );
+    assert!(!comparison.is_equal());
+    let fields: Vec<&str> = comparison.blobs[0]
+        .fields
+        .iter()
+        .map(|field| field.field.as_str())
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            "canary",
+            "fortified",
+            "fortify",
+            "nx",
+            "pie",
+            "relro",
+            "seperate_code",
+            "symbol_count"
+        ]
+    );
+    let relro = &comparison.blobs[0].fields[5];
+    assert_eq!(relro.left, json!("Full"));
+    assert_eq!(relro.right, json!("None"));
+
+    let text = comparison.to_string();
+    assert!(text.starts_with(
+        "./tests/binaries/elf/all (ELF64) vs ./tests/binaries/elf/none (ELF64)"
+    ));
+    assert!(text.lines().any(|line| {
+        line.split_whitespace().collect::<Vec<&str>>()
+            == ["relro", "Full", "None"]
+    }));
+}
+
+#[test]
+fn test_compare_formats() {
+    let comparison = Comparison::new(
+        &scan("./tests/binaries/elf/all"),
+        &scan("./tests/binaries/pe/debug_directories-clang_lld.exe.bin"),
+    );
+    let blob = &comparison.blobs[0];
+    // fields of only one format are compared with null
+    let canary = blob
+        .fields
+        .iter()
+        .find(|field| field.field == "canary")
+        .expect("canary missing");
+    assert_eq!(canary.right, json!(null));
+    let safeseh = blob
+        .fields
+        .iter()
+        .find(|field| field.field == "safeseh")
+        .expect("safeseh missing");
+    assert_eq!(safeseh.left, json!(null));
+}
+
+#[test]
+fn test_compare_rpath() {
+    let comparison = Comparison::new(
+        &scan("./tests/binaries/elf/none"),
+        &scan("./tests/binaries/elf/rpath"),
+    );
+    let rpath = comparison.blobs[0]
+        .fields
+        .iter()
+        .find(|field| field.field == "rpath")
+        .expect("rpath missing");
+    assert_eq!(rpath.left, json!("None"));
+    assert!(rpath.right.is_string());
+}
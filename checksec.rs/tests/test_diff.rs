@@ -0,0 +1,231 @@
+use checksec::{checksec, diff::Diff, severity::Level};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+mod utils;
+use utils::file_to_buf;
+
+fn scan(path: &str, filename: &str) -> checksec::binary::Binary {
+    checksec(&file_to_buf(&path.to_string()), filename.to_string())
+}
+
+fn run_checksec(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_checksec"))
+        .args(args)
+        .output()
+        .expect("checksec not run")
+}
+
+#[test]
+fn test_diff_unchanged() {
+    let baseline = [scan("./tests/binaries/elf/all", "bin/app")];
+    let current = [scan("./tests/binaries/elf/all", "bin/app")];
+    let diff = Diff::new(&baseline, &current);
+    assert_eq!(diff, Diff::default());
+    assert!(!diff.has_regressions());
+}
+
+#[test]
+fn test_diff_regression() {
+    let baseline = [scan("./tests/binaries/elf/all", "bin/app")];
+    let current = [scan("./tests/binaries/elf/none", "bin/app")];
+    let diff = Diff::new(&baseline, &current);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].file, PathBuf::from("bin/app"));
+    assert!(diff.has_regressions());
+
+    let canary = diff.changed[0]
+        .changes
+        .iter()
+        .find(|change| change.rule == "canary")
+        .expect("canary change missing");
+    assert!(canary.is_regression());
+    assert_eq!(canary.before_level, Level::None);
+    assert_eq!(canary.after_level, Level::Warning);
+    assert_eq!(
+        canary.to_string(),
+        "(ELF64) canary lost: Stack Canary enabled: true -> \
+        Stack Canary enabled: false"
+    );
+    // informational changes are no regressions
+    let symbols = diff.changed[0]
+        .changes
+        .iter()
+        .find(|change| change.rule == "symbols")
+        .expect("symbols change missing");
+    assert!(!symbols.is_regression() && !symbols.is_improvement());
+
+    // the reverse direction only gains mitigations
+    let diff = Diff::new(&current, &baseline);
+    assert!(!diff.has_regressions());
+    assert!(diff.changed[0]
+        .changes
+        .iter()
+        .any(|change| { change.rule == "canary" && change.is_improvement() }));
+}
+
+#[test]
+fn test_diff_added_removed() {
+    let baseline = [
+        scan("./tests/binaries/elf/all", "bin/app"),
+        scan("./tests/binaries/elf/dso.so", "bin/old"),
+    ];
+    let current = [
+        scan("./tests/binaries/elf/all", "bin/app"),
+        scan("./tests/binaries/elf/none", "bin/new"),
+    ];
+    let diff = Diff::new(&baseline, &current);
+    assert_eq!(diff.added, [PathBuf::from("bin/new")]);
+    assert_eq!(diff.removed, [PathBuf::from("bin/old")]);
+    assert!(diff.changed.is_empty());
+    assert!(!diff.has_regressions());
+}
+
+#[test]
+fn test_diff_moved() {
+    let baseline = [scan("./tests/binaries/elf/all", "bin/app")];
+    let current = [scan("./tests/binaries/elf/all", "usr/bin/app")];
+    assert_eq!(Diff::new(&baseline, &current), Diff::default());
+}
+
+#[test]
+fn test_diff_libraries() {
+    let mut baseline = scan("./tests/binaries/elf/all", "bin/app");
+    baseline.libraries = vec![scan("./tests/binaries/elf/all", "lib/libc.so")];
+    let mut current = scan("./tests/binaries/elf/all", "bin/app");
+    current.libraries = vec![scan("./tests/binaries/elf/none", "lib/libc.so")];
+    let diff = Diff::new(&[baseline], &[current]);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].file, PathBuf::from("lib/libc.so"));
+    assert!(diff.has_regressions());
+}
+
+#[test]
+fn test_diff_parse_error() {
+    let baseline = [scan("./tests/binaries/elf/all", "bin/app")];
+    let current = [checksec(b"not a binary", "bin/app".to_string())];
+    let diff = Diff::new(&baseline, &current);
+    assert!(diff.has_regressions());
+    assert_eq!(diff.changed[0].changes[0].rule, "parse_error");
+}
+
+#[test]
+fn test_diff_moved_by_hash() {
+    let baseline = [
+        scan("./tests/binaries/elf/all", "bin/app"),
+        scan("./tests/binaries/elf/dso.so", "lib/libdso.so"),
+    ];
+    let mut current = [
+        scan("./tests/binaries/elf/all", "usr/bin/app"),
+        scan("./tests/binaries/elf/dso.so", "lib/libdso.so"),
+    ];
+    // same results, but different contents
+    current[0].sha256 = Some("0".repeat(64));
+    let diff = Diff::new(&baseline, &current);
+    assert_eq!(diff.added, [PathBuf::from("usr/bin/app")]);
+    assert_eq!(diff.removed, [PathBuf::from("bin/app")]);
+
+    // reports without hashes fall back to the results
+    current[0].sha256 = None;
+    let diff = Diff::new(&baseline, &current);
+    assert_eq!(diff, Diff::default());
+}
+
+#[test]
+fn test_diff_fat_slices() {
+    let baseline = [scan("./tests/binaries/Mach-O/multiarch", "bin/app")];
+    let mut current = [scan("./tests/binaries/Mach-O/multiarch", "bin/app")];
+    // slices are matched by architecture, not by position
+    current[0].blobs.reverse();
+    assert_eq!(Diff::new(&baseline, &current), Diff::default());
+
+    let mut current = [scan("./tests/binaries/Mach-O/basic", "bin/app")];
+    current[0].blobs[0].slice = baseline[0].blobs[1].slice.clone();
+    let diff = Diff::new(&baseline, &current);
+    assert!(diff.changed[0].changes.iter().all(|change| {
+        change.architecture.as_deref() == Some("arm64e")
+            && change.to_string().starts_with("(MachO64 arm64e) ")
+    }));
+}
+
+#[test]
+fn test_diff_scan_roots() {
+    // scans of the directories of two releases
+    let baseline = [
+        scan("./tests/binaries/elf/all", "release-1.0/bin/app"),
+        scan("./tests/binaries/elf/dso.so", "release-1.0/lib/libdso.so"),
+    ];
+    let current = [
+        scan("./tests/binaries/elf/none", "release-1.1/bin/app"),
+        scan("./tests/binaries/elf/dso.so", "release-1.1/lib/libdso.so"),
+    ];
+    let diff = Diff::new(&baseline, &current);
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].file, PathBuf::from("release-1.1/bin/app"));
+    assert!(diff.has_regressions());
+
+    // single files are matched by their name
+    let diff = Diff::new(
+        &[scan("./tests/binaries/elf/all", "/build/1.0/app")],
+        &[scan("./tests/binaries/elf/none", "/build/1.1/app")],
+    );
+    assert_eq!(diff.changed[0].file, PathBuf::from("/build/1.1/app"));
+    assert!(diff.has_regressions());
+}
+
+#[test]
+fn test_diff_baseline_output() {
+    // two builds of the same program
+    let dir = std::env::temp_dir()
+        .join(format!("checksec-baseline-{}", std::process::id()));
+    for (build, binary) in [
+        ("1.0", "./tests/binaries/elf/all"),
+        ("1.1", "./tests/binaries/elf/none"),
+    ] {
+        fs::create_dir_all(dir.join(build)).expect("build not created");
+        fs::copy(binary, dir.join(build).join("app")).expect("not copied");
+    }
+    let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+    let output = run_checksec(&["-f", &path("1.0/app"), "--json"]);
+    fs::write(dir.join("1.0.json"), &output.stdout).expect("not written");
+
+    let output = run_checksec(&[
+        "-f",
+        &path("1.1/app"),
+        "--json",
+        "--baseline",
+        &path("1.0.json"),
+    ]);
+    // mitigations were lost
+    assert_eq!(output.status.code(), Some(1));
+    fs::write(dir.join("1.1.json"), &output.stdout).expect("not written");
+    let report: Value =
+        serde_json::from_slice(&output.stdout).expect("invalid JSON output");
+    assert_eq!(report["binaries"][0]["file"], json!(path("1.1/app")));
+    assert_eq!(report["diff"]["changed"][0]["file"], json!(path("1.1/app")));
+
+    // the output of a scan with a baseline is a baseline itself
+    let output = run_checksec(&[
+        "-f",
+        &path("1.1/app"),
+        "--sarif",
+        "--baseline",
+        &path("1.1.json"),
+    ]);
+    fs::remove_dir_all(&dir).expect("builds not removed");
+    assert!(output.status.success());
+    let report: Value =
+        serde_json::from_slice(&output.stdout).expect("invalid SARIF output");
+    assert!(report["runs"][0]["results"]
+        .as_array()
+        .expect("results missing")
+        .iter()
+        .all(|result| result["baselineState"] == "unchanged"));
+}
@@ -508,3 +508,192 @@ fn test_seperate_code_off() {
         }
     }
 }
+
+#[test]
+fn test_cet_full() {
+    let filename = "./tests/binaries/elf/cet_full".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(elf_result.ibt);
+            assert!(elf_result.shstk);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_cet_ibt_only() {
+    let filename = "./tests/binaries/elf/cet_ibt".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(elf_result.ibt);
+            assert!(!elf_result.shstk);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_cet_shstk_only() {
+    let filename = "./tests/binaries/elf/cet_shstk".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(!elf_result.ibt);
+            assert!(elf_result.shstk);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_cet_absent() {
+    let filename = "./tests/binaries/elf/nolibc".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(!elf_result.ibt);
+            assert!(!elf_result.shstk);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_aarch64_bti_pac() {
+    let filename = "./tests/binaries/elf/aarch64_bti_pac".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(elf_result.bti);
+            assert!(elf_result.pac);
+            assert!(!elf_result.ibt);
+            assert!(!elf_result.shstk);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_aarch64_bti_only() {
+    let filename = "./tests/binaries/elf/aarch64_bti".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(elf_result.bti);
+            assert!(!elf_result.pac);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_bti_pac_absent_on_x86() {
+    let filename = "./tests/binaries/elf/cet_full".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(!elf_result.bti);
+            assert!(!elf_result.pac);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_rwx_segment() {
+    let filename = "./tests/binaries/elf/rwx_segment".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert_eq!(elf_result.rwx_segments.len(), 1);
+            assert_eq!(elf_result.rwx_segments[0].index, 0);
+            // a non-executable stack does not make up for the segment
+            assert_eq!(elf_result.nx, elf::Nx::Disabled);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_no_rwx_segment() {
+    let filename = "./tests/binaries/elf/all".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(elf_result.rwx_segments.is_empty())
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_textrel() {
+    let filename = "./tests/binaries/elf/textrel_dso".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(elf_result.textrel)
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_no_textrel() {
+    let filename = "./tests/binaries/elf/dso.so".into();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            assert!(!elf_result.textrel)
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_expand_dst() {
+    let expand =
+        |entry| elf::expand_dst(entry, Some("/opt/app"), "X86_64", true);
+    assert_eq!(expand("$ORIGIN/lib"), Some("/opt/app/lib".into()));
+    assert_eq!(expand("${ORIGIN}/lib"), Some("/opt/app/lib".into()));
+    assert_eq!(
+        expand("/usr/$LIB/${PLATFORM}"),
+        Some("/usr/lib64/x86_64".into())
+    );
+    // not a token, kept verbatim
+    assert_eq!(expand("$ORIGIN_X/$FOO"), Some("$ORIGIN_X/$FOO".into()));
+    assert_eq!(expand("${ORIGIN"), Some("${ORIGIN".into()));
+    assert_eq!(expand("$ORIGIN$LIB"), Some("/opt/applib64".into()));
+    // known tokens without value discard the entry
+    assert_eq!(elf::expand_dst("$ORIGIN/lib", None, "X86_64", true), None);
+    assert_eq!(elf::expand_dst("/$PLATFORM", Some("/"), "PPC64", true), None);
+    assert_eq!(
+        elf::expand_dst("/usr/$LIB", None, "386", false),
+        Some("/usr/lib".into())
+    );
+}
@@ -0,0 +1,88 @@
+use checksec::{
+    binary::{BinSpecificProperties, BinType},
+    checksec,
+    compression::{compress, decompress},
+    error::{Error, ErrorInfo, ErrorKind},
+    try_get_blob_from_buf,
+};
+use serde_json::{json, Value};
+mod utils;
+use utils::file_to_buf;
+
+#[test]
+fn test_unsupported_format() {
+    let mut zip = b"PK\x03\x04\x14\x00\x00\x00\x08\x00".to_vec();
+    zip.resize(64, 0);
+    let err = try_get_blob_from_buf(&zip).expect_err("zip parsed as binary");
+    assert_eq!(err.kind(), ErrorKind::UnsupportedFormat);
+    assert!(
+        matches!(err, Error::UnsupportedFormat { ref file_type } if file_type == "zip")
+    );
+    assert_eq!(err.to_string(), "Unsupported File Format (File Type: zip)");
+
+    let info = ErrorInfo::from(&err);
+    assert_eq!(info.file_type.as_deref(), Some("zip"));
+    assert_eq!(
+        serde_json::to_value(&info).expect("serialization failed"),
+        json!({
+            "kind": "UnsupportedFormat",
+            "message": "Unsupported File Format (File Type: zip)",
+            "file_type": "zip",
+        })
+    );
+}
+
+#[test]
+fn test_truncated() {
+    let filename = "./tests/binaries/elf/all".into();
+    let buf = file_to_buf(&filename);
+    let err = try_get_blob_from_buf(&buf[..128])
+        .expect_err("truncated binary parsed");
+    assert!(matches!(err.kind(), ErrorKind::Truncated | ErrorKind::Malformed));
+}
+
+#[test]
+fn test_error_blob() {
+    let binary = checksec(&[b'x'; 64], "./notes.txt".into());
+    assert_eq!(binary.blobs.len(), 1);
+    assert_eq!(binary.blobs[0].binarytype, BinType::Error);
+    let BinSpecificProperties::Error(ref info) = binary.blobs[0].properties
+    else {
+        panic!("expected an error blob");
+    };
+    assert_eq!(info.kind, ErrorKind::UnsupportedFormat);
+    assert_eq!(info.file_type.as_deref(), Some("Unknown"));
+
+    // too small to detect the format
+    let binary = checksec(b"not a binary", "./notes.txt".into());
+    let BinSpecificProperties::Error(ref info) = binary.blobs[0].properties
+    else {
+        panic!("expected an error blob");
+    };
+    assert_eq!(info.kind, ErrorKind::Malformed);
+
+    let json: Value =
+        serde_json::to_value(&binary).expect("serialization failed");
+    let error = &json["blobs"][0]["properties"]["Error"];
+    assert_eq!(error["kind"], json!("Malformed"));
+    assert_eq!(error["message"], json!(info.message));
+
+    // binary formats keep the struct
+    let compressed = compress(&binary).expect("compression failed");
+    let decompressed: checksec::binary::Binary =
+        decompress(compressed.as_bytes()).expect("decompression failed");
+    let BinSpecificProperties::Error(ref decompressed) =
+        decompressed.blobs[0].properties
+    else {
+        panic!("expected an error blob");
+    };
+    assert_eq!(decompressed, info);
+}
+
+#[test]
+fn test_decompress_error() {
+    let err = decompress::<Value>(b"not base64!")
+        .expect_err("invalid data decompressed");
+    assert_eq!(err.kind(), ErrorKind::Compression);
+    assert_eq!(ErrorInfo::from(err).kind, ErrorKind::Compression);
+}
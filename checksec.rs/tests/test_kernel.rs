@@ -0,0 +1,172 @@
+use checksec::{
+    kernel::{KernelConfig, KernelReport, Source, Status, Sysctl},
+    sarif::get_sarif_kernel_report,
+    severity::SeverityProfile,
+};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn status(report: &KernelReport, name: &str) -> Option<Status> {
+    report
+        .checks
+        .iter()
+        .find(|check| check.name == name)
+        .map(|check| check.status)
+}
+
+#[test]
+fn test_kernel_config() {
+    let config =
+        KernelConfig::from_file(Path::new("./tests/binaries/kernel/config"))
+            .expect("config not read");
+    assert_eq!(config.get(&["CONFIG_STACKPROTECTOR_STRONG"]), Some("y"));
+    assert_eq!(config.get(&["CONFIG_MODULE_SIG_FORCE"]), Some("n"));
+    assert_eq!(
+        config.get(&["CONFIG_CC_VERSION_TEXT"]),
+        Some("gcc (Ubuntu 13.2.0-23ubuntu4) 13.2.0")
+    );
+    assert_eq!(config.get(&["CONFIG_DEVKMEM"]), None);
+
+    // /proc/config.gz is gzip compressed
+    let compressed = KernelConfig::from_file(Path::new(
+        "./tests/binaries/kernel/config.gz",
+    ))
+    .expect("compressed config not read");
+    assert_eq!(compressed.options, config.options);
+}
+
+#[test]
+fn test_kernel_report() {
+    let config =
+        KernelConfig::from_file(Path::new("./tests/binaries/kernel/config"))
+            .expect("config not read");
+    let report = KernelReport::new(&config, None);
+    assert_eq!(report.sysctl, None);
+    assert!(report.checks.iter().all(|check| check.source == Source::Config));
+
+    for name in [
+        "CONFIG_STACKPROTECTOR_STRONG",
+        "CONFIG_FORTIFY_SOURCE",
+        "CONFIG_RANDOMIZE_BASE",
+        "CONFIG_STRICT_KERNEL_RWX",
+        "CONFIG_DEFAULT_MMAP_MIN_ADDR",
+        // not set is the expected value
+        "CONFIG_DEVKMEM",
+        // older name of the option
+        "CONFIG_MITIGATION_RETPOLINE",
+    ] {
+        assert_eq!(status(&report, name), Some(Status::Pass), "{name}");
+    }
+    for name in ["CONFIG_COMPAT_BRK", "CONFIG_MODULE_SIG_FORCE"] {
+        assert_eq!(status(&report, name), Some(Status::Fail), "{name}");
+    }
+    // checks of other architectures are skipped
+    assert_eq!(status(&report, "CONFIG_ARM64_BTI_KERNEL"), None);
+    assert_eq!(
+        report.failures().map(|check| check.name.as_str()).collect::<Vec<_>>(),
+        vec![
+            "CONFIG_INIT_ON_ALLOC_DEFAULT_ON",
+            "CONFIG_BUG_ON_DATA_CORRUPTION",
+            "CONFIG_MODULE_SIG_FORCE",
+            "CONFIG_COMPAT_BRK",
+        ]
+    );
+}
+
+#[test]
+fn test_kernel_sysctl() {
+    let config =
+        KernelConfig::from_file(Path::new("./tests/binaries/kernel/config"))
+            .expect("config not read");
+    let sysctl =
+        Sysctl::from_path(Path::new("./tests/binaries/kernel/sysctl.txt"))
+            .expect("sysctl snapshot not read");
+    let report = KernelReport::new(&config, Some(&sysctl));
+    assert_eq!(
+        status(&report, "kernel.randomize_va_space"),
+        Some(Status::Pass)
+    );
+    assert_eq!(
+        status(&report, "kernel.perf_event_paranoid"),
+        Some(Status::Pass)
+    );
+    assert_eq!(status(&report, "fs.suid_dumpable"), Some(Status::Fail));
+    assert_eq!(status(&report, "net.core.bpf_jit_harden"), Some(Status::Fail));
+    // yama is not built into the kernel of the snapshot
+    assert_eq!(
+        status(&report, "kernel.yama.ptrace_scope"),
+        Some(Status::Unknown)
+    );
+
+    // a /proc/sys tree
+    let sysctl = Sysctl::from_path(Path::new("./tests/binaries/kernel/sys"))
+        .expect("sysctl tree not read");
+    assert_eq!(
+        sysctl.settings.get("kernel.randomize_va_space").map(String::as_str),
+        Some("2")
+    );
+    let report = KernelReport::new(&config, Some(&sysctl));
+    assert_eq!(status(&report, "kernel.kptr_restrict"), Some(Status::Fail));
+    assert_eq!(status(&report, "fs.protected_symlinks"), Some(Status::Pass));
+}
+
+#[test]
+fn test_kernel_sarif() {
+    let config =
+        KernelConfig::from_file(Path::new("./tests/binaries/kernel/config"))
+            .expect("config not read");
+    let sysctl =
+        Sysctl::from_path(Path::new("./tests/binaries/kernel/sysctl.txt"))
+            .expect("sysctl snapshot not read");
+    let report = KernelReport::new(&config, Some(&sysctl));
+    let sarif: Value = serde_json::from_str(
+        &get_sarif_kernel_report(&report, &SeverityProfile::default())
+            .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF");
+    let run = &sarif["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().expect("rules");
+    let results = run["results"].as_array().expect("results");
+    assert_eq!(results.len(), report.checks.len());
+    for result in results {
+        let index =
+            usize::try_from(result["ruleIndex"].as_u64().expect("ruleIndex"))
+                .expect("ruleIndex");
+        assert_eq!(rules[index]["id"], result["ruleId"]);
+    }
+    let result = |rule: &str| {
+        results
+            .iter()
+            .find(|result| result["ruleId"] == rule)
+            .expect("result missing")
+    };
+    assert_eq!(result("CONFIG_COMPAT_BRK")["level"], json!("warning"));
+    assert_eq!(result("CONFIG_STRICT_KERNEL_RWX")["level"], json!("none"));
+    assert_eq!(result("kernel.yama.ptrace_scope")["level"], json!("note"));
+    // sysctl results point to the snapshot
+    let uri = |rule: &str| {
+        result(rule)["locations"][0]["physicalLocation"]["artifactLocation"]
+            ["uri"]
+            .clone()
+    };
+    assert_eq!(
+        uri("fs.suid_dumpable"),
+        json!("./tests/binaries/kernel/sysctl.txt")
+    );
+    assert_eq!(
+        uri("CONFIG_COMPAT_BRK"),
+        json!("./tests/binaries/kernel/config")
+    );
+
+    let strict = SeverityProfile::builtin("strict").expect("profile");
+    let sarif: Value = serde_json::from_str(
+        &get_sarif_kernel_report(&report, &strict).expect("SARIF failed"),
+    )
+    .expect("invalid SARIF");
+    assert!(sarif["runs"][0]["results"]
+        .as_array()
+        .expect("results")
+        .iter()
+        .any(|result| result["ruleId"] == "CONFIG_COMPAT_BRK"
+            && result["level"] == "error"));
+}
@@ -0,0 +1,112 @@
+use checksec::{
+    binary::BinSpecificProperties,
+    checksec,
+    elf::{self, KernelModule, Properties},
+    sarif::get_sarif_report,
+};
+use goblin::elf::Elf;
+use serde_json::{json, Value};
+mod utils;
+use utils::file_to_buf;
+
+fn elf_result(filename: &str) -> elf::CheckSecResults {
+    let filename = filename.to_string();
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => elf_result.clone(),
+        _ => panic!("Checksec failed"),
+    }
+}
+
+#[test]
+fn test_kernel_module() {
+    let elf_result = elf_result("./tests/binaries/elf/kmod.ko");
+    assert_eq!(elf_result.pie, elf::PIE::Module);
+    let module = elf_result.kernel_module.expect("module not detected");
+    assert_eq!(
+        module,
+        KernelModule {
+            signed: false,
+            vermagic: Some(
+                "6.8.0-45-generic SMP preempt mod_unload modversions ".into()
+            ),
+            retpoline: true,
+            cfi: false,
+            ibt: true,
+            stack_protector: true,
+            license: Some("GPL".into()),
+        }
+    );
+    // module properties count as the generic mitigations
+    assert!(elf_result.canary);
+    assert!(elf_result.ibt);
+}
+
+#[test]
+fn test_kernel_module_signed() {
+    let elf_result = elf_result("./tests/binaries/elf/kmod_signed.ko");
+    assert!(elf_result.kernel_module.expect("module not detected").signed);
+}
+
+#[test]
+fn test_kernel_module_none() {
+    let elf_result = elf_result("./tests/binaries/elf/kmod_none.ko");
+    let module = elf_result.kernel_module.expect("module not detected");
+    assert!(!module.signed);
+    assert!(!module.retpoline);
+    assert!(!module.ibt);
+    assert!(!module.stack_protector);
+    assert!(!elf_result.canary);
+}
+
+#[test]
+fn test_kernel_module_endbr() {
+    let filename = "./tests/binaries/elf/kmod.ko".to_string();
+    let buf = file_to_buf(&filename);
+    let elf = Elf::parse(&buf).expect("parse failed");
+    assert!(elf.is_kernel_module());
+    assert!(elf.has_endbr(&buf));
+
+    let filename = "./tests/binaries/elf/kmod_none.ko".to_string();
+    let buf = file_to_buf(&filename);
+    let elf = Elf::parse(&buf).expect("parse failed");
+    assert!(!elf.has_endbr(&buf));
+    assert_eq!(elf.get_modinfo(&buf, "name").as_deref(), Some("kmod"));
+    assert_eq!(elf.get_modinfo(&buf, "author"), None);
+
+    // a single function with `endbr` among functions without
+    let filename = "./tests/binaries/elf/kmod_partial_ibt.ko".to_string();
+    let buf = file_to_buf(&filename);
+    let elf = Elf::parse(&buf).expect("parse failed");
+    assert!(!elf.has_endbr(&buf));
+}
+
+#[test]
+fn test_relocatable_object() {
+    // plain objects are no kernel modules
+    let elf_result = elf_result("./tests/binaries/elf/rel.o");
+    assert_eq!(elf_result.pie, elf::PIE::REL);
+    assert_eq!(elf_result.kernel_module, None);
+}
+
+#[test]
+fn test_kernel_module_sarif() {
+    let filename = "./tests/binaries/elf/kmod_none.ko".to_string();
+    let binaries = [checksec(&file_to_buf(&filename), filename)];
+    let report: Value = serde_json::from_str(
+        &get_sarif_report(&binaries).expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF");
+    let level = |rule: &str| {
+        report["runs"][0]["results"]
+            .as_array()
+            .expect("results missing")
+            .iter()
+            .find(|result| result["ruleId"] == rule)
+            .map(|result| result["level"].clone())
+    };
+    assert_eq!(level("module_signature"), Some(json!("warning")));
+    assert_eq!(level("retpoline"), Some(json!("warning")));
+    assert_eq!(level("module_license"), Some(json!("note")));
+    assert_eq!(level("PIE"), Some(json!("none")));
+}
@@ -0,0 +1,232 @@
+#![cfg(target_os = "linux")]
+use checksec::binary::BinSpecificProperties;
+use checksec::checksec;
+use checksec::elf::LibraryLookup;
+use checksec::ldso::LdSoCache;
+use checksec::shared::{resolve_in_sysroot, Rpath, VecRpath};
+use std::path::Path;
+mod utils;
+use utils::file_to_buf;
+
+const SYSROOT: &str = "./tests/binaries/ldso/sysroot";
+
+fn check_cache(filename: &str) {
+    let cache = LdSoCache::from_file(Path::new(filename))
+        .expect("ld.so.cache parsing failed");
+    assert_eq!(cache.entries().len(), 4);
+    assert_eq!(cache.entries()[3].soname, "libbar.so.2");
+    assert_eq!(cache.entries()[3].path, Path::new("/usr/lib/libbar.so.2"));
+
+    let candidates = |architecture, is_64| {
+        cache
+            .candidates("libfoo.so.1", architecture, is_64)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        candidates("X86_64", true),
+        [Path::new("/usr/lib/x86_64-linux-gnu/libfoo.so.1")]
+    );
+    assert_eq!(
+        candidates("386", false),
+        [Path::new("/usr/lib/i386-linux-gnu/libfoo.so.1")]
+    );
+    assert_eq!(
+        candidates("AARCH64", true),
+        [Path::new("/usr/lib/aarch64-linux-gnu/libfoo.so.1")]
+    );
+    assert!(candidates("PPC64", true).is_empty());
+    // FLAG_ELF entries are usable by every target
+    assert_eq!(cache.candidates("libbar.so.2", "PPC64", true).count(), 1);
+}
+
+#[test]
+fn test_ldso_cache_old() {
+    check_cache("./tests/binaries/ldso/ld.so.cache-old");
+}
+
+#[test]
+fn test_ldso_cache_new() {
+    check_cache("./tests/binaries/ldso/ld.so.cache-new");
+}
+
+#[test]
+fn test_ldso_cache_compat() {
+    check_cache("./tests/binaries/ldso/ld.so.cache-compat");
+}
+
+#[test]
+fn test_ldso_cache_invalid() {
+    assert!(LdSoCache::parse(b"not a cache").is_err());
+    let truncated = std::fs::read("./tests/binaries/ldso/ld.so.cache-new")
+        .expect("reading ld.so.cache fixture failed");
+    assert!(LdSoCache::parse(&truncated[..100]).is_err());
+}
+
+#[test]
+fn test_sysroot_resolve() {
+    let sysroot = Path::new(SYSROOT);
+    // absolute symlink targets are resolved inside the sysroot
+    assert_eq!(
+        resolve_in_sysroot(sysroot, Path::new("/opt/lib/libconf.so.1")),
+        Some(sysroot.join("opt/lib/libconf.so.1.0"))
+    );
+    // ".." can not leave the sysroot
+    assert_eq!(
+        resolve_in_sysroot(sysroot, Path::new("/opt/../../../etc/ld.so.conf")),
+        Some(sysroot.join("etc/ld.so.conf"))
+    );
+    assert_eq!(resolve_in_sysroot(sysroot, Path::new("/bin/sh")), None);
+}
+
+#[test]
+fn test_sysroot_lookup() {
+    let sysroot = Path::new(SYSROOT);
+    let filename = format!("{SYSROOT}/usr/bin/app");
+    let buf = file_to_buf(&filename);
+    let lookup = LibraryLookup::with_sysroot(sysroot)
+        .expect("sysroot lookup initialization failed");
+    match &checksec(&buf, filename.clone()).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            let binarypath = Path::new(&filename);
+            // found through the included ld.so.conf.d directory
+            assert_eq!(
+                lookup.lookup(binarypath, elf_result, "libconf.so.1"),
+                Some(sysroot.join("opt/lib/libconf.so.1.0"))
+            );
+            // found through RPATH $ORIGIN/../lib
+            assert_eq!(
+                lookup.lookup(binarypath, elf_result, "librpath.so.1"),
+                Some(sysroot.join("usr/lib/librpath.so.1"))
+            );
+            assert_eq!(
+                lookup.interpreter(elf_result),
+                Some(sysroot.join("opt/lib/libconf.so.1.0"))
+            );
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_sysroot_dst_lookup() {
+    let sysroot = Path::new(SYSROOT);
+    let filename = format!("{SYSROOT}/usr/bin/app-dst");
+    let buf = file_to_buf(&filename);
+    let lookup = LibraryLookup::with_sysroot(sysroot)
+        .expect("sysroot lookup initialization failed");
+    match &checksec(&buf, filename.clone()).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            let binarypath = Path::new(&filename);
+            // ${ORIGIN}/../lib
+            assert_eq!(
+                lookup.lookup(binarypath, elf_result, "librpath.so.1"),
+                Some(sysroot.join("usr/lib/librpath.so.1"))
+            );
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_sysroot_lookup_order() {
+    let sysroot = Path::new(SYSROOT);
+    let lookup = LibraryLookup::with_sysroot(sysroot)
+        .expect("sysroot lookup initialization failed");
+    let filename = format!("{SYSROOT}/usr/bin/app-runpath");
+    let buf = file_to_buf(&filename);
+    match &checksec(&buf, filename.clone()).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            // RUNPATH comes before the default directories, the 32-bit
+            // library of /opt/lib32 is skipped
+            assert_eq!(
+                lookup.lookup(
+                    Path::new(&filename),
+                    elf_result,
+                    "librpath.so.1"
+                ),
+                Some(sysroot.join("opt/runpath/librpath.so.1"))
+            );
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+
+    let filename = format!("{SYSROOT}/usr/bin/app");
+    let buf = file_to_buf(&filename);
+    match &mut checksec(&buf, filename.clone()).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            let binarypath = Path::new(&filename);
+            // RPATH entries of another ELF class are skipped as well
+            elf_result.rpath = VecRpath::new(vec![
+                Rpath::Yes("/opt/lib32".into()),
+                Rpath::Yes("$ORIGIN/../lib".into()),
+            ]);
+            assert_eq!(
+                lookup.lookup(binarypath, elf_result, "librpath.so.1"),
+                Some(sysroot.join("usr/lib/librpath.so.1"))
+            );
+            // RPATH is ignored if there is a RUNPATH
+            elf_result.runpath =
+                VecRpath::new(vec![Rpath::Yes("/opt/runpath".into())]);
+            assert_eq!(
+                lookup.lookup(binarypath, elf_result, "librpath.so.1"),
+                Some(sysroot.join("opt/runpath/librpath.so.1"))
+            );
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_check_search_dirs() {
+    let sysroot = Path::new(SYSROOT);
+    let filename = format!("{SYSROOT}/usr/bin/app-dst");
+    let buf = file_to_buf(&filename);
+    match &mut checksec(&buf, filename.clone()).blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => {
+            elf_result.check_search_dirs(Path::new(&filename), sysroot);
+            assert_eq!(
+                *elf_result.rpath,
+                [
+                    Rpath::Yes("${ORIGIN}/../lib".into()),
+                    Rpath::Missing("$ORIGIN/missing".into()),
+                    Rpath::Relative("relative".into()),
+                    Rpath::Missing("/$LIB".into()),
+                    Rpath::Relative("$ORIGINAL".into()),
+                ]
+            );
+            assert_eq!(*elf_result.runpath, [Rpath::None]);
+        }
+        _ => {
+            panic!("Checksec failed")
+        }
+    }
+}
+
+#[test]
+fn test_check_dir_writable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir()
+        .join(format!("checksec-rpath-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("creating test directory failed");
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777))
+        .expect("setting test directory permissions failed");
+    let writable = Rpath::check_dir("rw", Some(&dir), Path::new("/"));
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755))
+        .expect("setting test directory permissions failed");
+    let owner_only = Rpath::check_dir("ro", Some(&dir), Path::new("/"));
+    std::fs::remove_dir(&dir).expect("removing test directory failed");
+
+    assert_eq!(writable, Rpath::YesRW("rw".into()));
+    assert!(writable.is_insecure());
+    assert_eq!(owner_only, Rpath::Yes("ro".into()));
+    assert!(!owner_only.is_insecure());
+}
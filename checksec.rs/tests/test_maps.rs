@@ -0,0 +1,152 @@
+#![cfg(all(target_os = "linux", feature = "maps"))]
+use checksec::{
+    binary::Binary,
+    checksec,
+    proc::{
+        MapEntry, MapFlags, MapSummary, MappedFile, ProcFs, Process,
+        Processes, Region,
+    },
+    sarif::get_sarif_process_report,
+    severity::SeverityProfile,
+};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+mod utils;
+use utils::file_to_buf;
+
+const SNAPSHOT: &str = "./tests/binaries/proc/snapshot";
+
+fn map(start: usize, flags: &str, pathname: Option<&str>) -> MapEntry {
+    MapEntry {
+        region: Region::new(start, start + 0x1000),
+        flags: MapFlags::new(flags),
+        pathname: pathname.map(PathBuf::from),
+    }
+}
+
+fn maps() -> Vec<MapEntry> {
+    vec![
+        map(0x1000, "r-xp", Some("/usr/bin/app")),
+        map(0x2000, "rw-p", Some("/usr/bin/app")),
+        // text relocations of a library
+        map(0x3000, "rwxp", Some("/usr/lib/libold.so")),
+        map(0x4000, "rwxp", Some("[heap]")),
+        map(0x5000, "r-xp", None),
+        map(0x6000, "rwxp", Some("[anon:jit]")),
+        map(0x7000, "r-xp", Some("[vdso]")),
+        map(0x8000, "rw-p", Some("[stack]")),
+        map(0x9000, "-wxp", Some("/usr/lib/libodd.so")),
+    ]
+}
+
+#[test]
+fn test_map_summary() {
+    let summary = MapSummary::new(&maps());
+    assert_eq!(
+        summary,
+        MapSummary {
+            rwx: 3,
+            anonymous_exec: 2,
+            exec_stack: false,
+            exec_heap: true,
+            writable_file_exec: 2,
+        }
+    );
+    assert!(summary.has_findings());
+    // readable ones are counted as RWX as well
+    let summary =
+        MapSummary::new(&[map(0x3000, "rwxp", Some("/usr/lib/libold.so"))]);
+    assert_eq!((summary.rwx, summary.writable_file_exec), (1, 1));
+
+    let summary = MapSummary::new(&[
+        map(0x1000, "r-xp", Some("/usr/bin/app")),
+        map(0x2000, "rwxp", Some("[stack]")),
+    ]);
+    assert!(summary.exec_stack);
+    assert_eq!(summary.anonymous_exec, 0);
+
+    let summary = MapSummary::new(&maps()[..2]);
+    assert_eq!(summary, MapSummary::default());
+    assert!(!summary.has_findings());
+}
+
+#[test]
+fn test_map_summary_sarif() {
+    let maps = maps();
+    let processes = Processes::new(vec![Process {
+        pid: 1,
+        context: None,
+        binary: Binary::new("/usr/bin/app".into(), vec![]),
+        runtime: None,
+        map_summary: Some(MapSummary::new(&maps)),
+        maps: Some(maps),
+        libraries: None,
+        stale: vec![],
+    }]);
+    let sarif: Value = serde_json::from_str(
+        &get_sarif_process_report(&processes, &SeverityProfile::default())
+            .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF");
+    let run = &sarif["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().expect("rules");
+    let results = run["results"].as_array().expect("results");
+    assert_eq!(results.len(), 5);
+    for result in results {
+        let index =
+            usize::try_from(result["ruleIndex"].as_u64().expect("ruleIndex"))
+                .expect("ruleIndex");
+        assert_eq!(rules[index]["id"], result["ruleId"]);
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]
+                ["uri"],
+            json!("/usr/bin/app")
+        );
+    }
+    let level = |rule: &str| {
+        results
+            .iter()
+            .find(|result| result["ruleId"] == rule)
+            .map(|result| result["level"].clone())
+    };
+    assert_eq!(level("rwx_mappings"), Some(json!("error")));
+    assert_eq!(level("anonymous_exec_mappings"), Some(json!("warning")));
+    assert_eq!(level("exec_stack_mapping"), Some(json!("none")));
+    assert_eq!(level("exec_heap_mapping"), Some(json!("error")));
+    assert_eq!(level("writable_file_exec_mappings"), Some(json!("error")));
+}
+
+#[test]
+fn test_procfs_snapshot_maps() {
+    let procfs = ProcFs::new(SNAPSHOT);
+    let maps = procfs.parse_maps(4242).expect("maps not read");
+    assert_eq!(maps.len(), 9);
+    assert_eq!(
+        procfs.libraries(4242, &maps, Some(Path::new("/usr/bin/app"))),
+        vec![
+            MappedFile {
+                file: PathBuf::from("/usr/lib/libdso.so.1"),
+                source: Path::new(SNAPSHOT)
+                    .join("4242/root/usr/lib/libdso.so.1"),
+                stale: false,
+            },
+            // the deleted image is only available through map_files
+            MappedFile {
+                file: PathBuf::from("/usr/lib/libgone.so"),
+                source: Path::new(SNAPSHOT)
+                    .join("4242/map_files/7f3a20000000-7f3a20001000"),
+                stale: true,
+            },
+        ]
+    );
+
+    let binary = checksec(
+        &file_to_buf(&format!("{SNAPSHOT}/4242/root/usr/bin/app")),
+        "/usr/bin/app".into(),
+    );
+    let process = procfs.process(4242, binary, None);
+    let summary = process.map_summary.expect("maps not read");
+    assert_eq!(summary.rwx, 1);
+    assert_eq!(summary.anonymous_exec, 1);
+    assert!(!summary.exec_heap);
+}
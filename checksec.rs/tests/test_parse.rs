@@ -0,0 +1,257 @@
+use checksec::{
+    binary::{BinSpecificProperties, BinType, Binary},
+    checksec, checksec_binaries,
+    error::{ErrorInfo, ErrorKind},
+    get_blob_from_buf, try_checksec_binaries,
+};
+use std::path::{Path, PathBuf};
+mod utils;
+use utils::file_to_buf;
+
+// Static library in the common ar format
+fn archive(members: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut archive = b"!<arch>\n".to_vec();
+    for (name, data) in members {
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            format!("{name}/"),
+            0,
+            0,
+            0,
+            644,
+            data.len()
+        );
+        archive.extend(header.as_bytes());
+        archive.extend(*data);
+        if data.len() % 2 == 1 {
+            archive.push(b'\n');
+        }
+    }
+    archive
+}
+
+fn blob_types(binary: &Binary) -> Vec<BinType> {
+    binary.blobs.iter().map(|blob| blob.binarytype.clone()).collect()
+}
+
+#[test]
+fn test_parse_single() {
+    let filename = "./tests/binaries/elf/all".to_string();
+    let buf = file_to_buf(&filename);
+    let binaries = checksec_binaries(&buf, Path::new(&filename));
+    assert_eq!(binaries.len(), 1);
+    assert_eq!(
+        serde_json::to_value(&binaries[0]).expect("serialization failed"),
+        serde_json::to_value(checksec(&buf, filename))
+            .expect("serialization failed")
+    );
+}
+
+#[test]
+fn test_parse_fat_macho() {
+    let filename = "./tests/binaries/Mach-O/multiarch".to_string();
+    let buf = file_to_buf(&filename);
+    let binaries = checksec_binaries(&buf, Path::new(&filename));
+    assert_eq!(binaries.len(), 1);
+    assert_eq!(binaries[0].blobs.len(), 2);
+    assert_eq!(blob_types(&binaries[0]), vec![BinType::MachO64; 2]);
+}
+
+#[test]
+fn test_parse_archive() {
+    let object = file_to_buf(&"./tests/binaries/elf/rel.o".into());
+    let notes = [b'x'; 65];
+    let archive = archive(&[("rel.o", &object), ("notes.txt", &notes)]);
+
+    let binaries = checksec_binaries(&archive, Path::new("libfoo.a"));
+    assert_eq!(
+        binaries.iter().map(|binary| &binary.file).collect::<Vec<_>>(),
+        vec![
+            &PathBuf::from("libfoo.a\u{2794}notes.txt"),
+            &PathBuf::from("libfoo.a\u{2794}rel.o"),
+        ]
+    );
+    assert_eq!(blob_types(&binaries[0]), vec![BinType::Error]);
+    assert_eq!(blob_types(&binaries[1]), vec![BinType::Elf64]);
+
+    // the blobs of all members, in alphabetical order of the members
+    assert_eq!(
+        get_blob_from_buf(&archive)
+            .iter()
+            .map(|blob| blob.binarytype.clone())
+            .collect::<Vec<_>>(),
+        vec![BinType::Error, BinType::Elf64]
+    );
+}
+
+#[test]
+fn test_parse_error() {
+    let file = Path::new("./notes.txt");
+    let err = try_checksec_binaries(&[b'x'; 64], file)
+        .expect_err("text file parsed as binary");
+    assert_eq!(err.kind(), ErrorKind::UnsupportedFormat);
+
+    let binaries = checksec_binaries(&[b'x'; 64], file);
+    assert_eq!(binaries.len(), 1);
+    assert_eq!(binaries[0].file, file);
+    assert_eq!(blob_types(&binaries[0]), vec![BinType::Error]);
+}
+
+#[test]
+fn test_parse_metadata() {
+    let filename = "./tests/binaries/elf/all".to_string();
+    let buf = file_to_buf(&filename);
+    let binary = &checksec_binaries(&buf, Path::new(&filename))[0];
+    assert_eq!(
+        binary.sha256.as_deref(),
+        Some(
+            "130143dfd348ceb43a0da38be1376d0a9a1f051c30c2cf398e09943415c682d0"
+        )
+    );
+    assert_eq!(binary.size, Some(buf.len() as u64));
+    // only known for files on disk
+    assert_eq!(binary.mtime, None);
+    assert_eq!(
+        binary.blobs[0].build_id.as_deref(),
+        Some("18f64b74cb5ee1a63ced387b0c9abeacab321626")
+    );
+
+    for (filename, build_id) in [
+        (
+            "./tests/binaries/pe/debug_directories-clang_lld.exe.bin",
+            "ABF6BAC8-98B2-9ED1-4C4C-44205044422E",
+        ),
+        (
+            "./tests/binaries/Mach-O/basic",
+            "590D2C0D-68E4-38E4-9FCC-AD71F8EAD734",
+        ),
+    ] {
+        let filename = filename.to_string();
+        let binary = checksec(&file_to_buf(&filename), filename);
+        assert_eq!(binary.blobs[0].build_id.as_deref(), Some(build_id));
+    }
+
+    // archive members are hashed separately
+    let object = file_to_buf(&"./tests/binaries/elf/rel.o".into());
+    let archive = archive(&[("rel.o", &object)]);
+    let binaries = checksec_binaries(&archive, Path::new("libfoo.a"));
+    assert_eq!(binaries[0].size, Some(object.len() as u64));
+    assert_ne!(
+        binaries[0].sha256,
+        checksec(&archive, "libfoo.a".into()).sha256
+    );
+}
+
+#[test]
+fn test_parse_old_report() {
+    // reports without metadata can still be read
+    let binary: Binary = serde_json::from_str(
+        r#"{"file": "bin/app", "blobs": [], "libraries": []}"#,
+    )
+    .expect("deserialization failed");
+    assert_eq!(binary.sha256, None);
+
+    // neither are ELF reports without the newer checks
+    let binaries: Vec<Binary> = serde_json::from_str(
+        r#"[{"blobs":[{"binarytype":"Elf64","properties":{"Elf":{
+            "architecture":"X86_64","asan":false,"bitness":64,"canary":true,
+            "clang_cfi":false,"clang_safestack":false,"dyn_linking":true,
+            "dynlibs":["libc.so.6"],"endianness":"Little","fortifiable":2,
+            "fortified":2,"fortify":"Full",
+            "interpreter":"/lib64/ld-linux-x86-64.so.2","nx":"Enabled",
+            "pie":"PIE","relro":"Full","rpath":{"paths":["None"]},
+            "runpath":{"paths":["None"]},"seperate_code":true,
+            "stack_clash_protection":false,"symbol_count":0}}}],
+          "file":"bin/app","libraries":[]}]"#,
+    )
+    .expect("deserialization failed");
+    let BinSpecificProperties::Elf(elf) = &binaries[0].blobs[0].properties
+    else {
+        panic!("not an ELF blob");
+    };
+    assert!(elf.canary);
+    assert!(!elf.ibt && !elf.shstk && !elf.bti && !elf.pac && !elf.textrel);
+    assert!(elf.rwx_segments.is_empty());
+    assert_eq!(elf.kernel_module, None);
+
+    // error blobs used to have a plain message
+    let binary: Binary = serde_json::from_str(
+        r#"{"file": "bin/app", "libraries": [], "blobs": [{
+            "binarytype": "Error",
+            "properties": {"Error": "Malformed entity: bad magic"}}]}"#,
+    )
+    .expect("deserialization failed");
+    let BinSpecificProperties::Error(info) = &binary.blobs[0].properties
+    else {
+        panic!("not an error blob");
+    };
+    assert_eq!(
+        info,
+        &ErrorInfo {
+            kind: ErrorKind::Malformed,
+            message: "Malformed entity: bad magic".into(),
+            file_type: None,
+        }
+    );
+}
+
+#[test]
+fn test_parse_fat_slices() {
+    let filename = "./tests/binaries/Mach-O/multiarch".to_string();
+    let binary = checksec(&file_to_buf(&filename), filename);
+    let slices: Vec<_> = binary
+        .blobs
+        .iter()
+        .map(|blob| blob.slice.clone().expect("slice missing"))
+        .collect();
+    assert_eq!(slices[0].architecture, "x86_64");
+    assert_eq!(slices[0].cputype, 0x0100_0007);
+    assert_eq!(slices[0].offset, 16384);
+    assert_eq!(slices[0].size, 41328);
+    assert_eq!(slices[0].align, 14);
+    assert_eq!(slices[1].architecture, "arm64e");
+    assert_eq!(slices[1].cputype, 0x0100_000c);
+    assert_eq!(slices[1].offset, 65536);
+
+    // thin binaries have no slices
+    let filename = "./tests/binaries/Mach-O/basic".to_string();
+    let binary = checksec(&file_to_buf(&filename), filename);
+    assert_eq!(binary.blobs[0].slice, None);
+}
+
+#[test]
+fn test_parse_fat_archive() {
+    let object = file_to_buf(&"./tests/binaries/Mach-O/rel_cl.o".into());
+    let archive = archive(&[("rel_cl.o", &object)]);
+    // fat header with a x86_64 and an arm64 static library slice
+    let arches = [(0x0100_0007_u32, 3_u32), (0x0100_000c, 0)];
+    let mut fat = Vec::new();
+    fat.extend(0xcafe_babe_u32.to_be_bytes());
+    fat.extend(2_u32.to_be_bytes());
+    let align = 12_u32;
+    for (index, (cputype, cpusubtype)) in (1_u32..).zip(arches) {
+        fat.extend(cputype.to_be_bytes());
+        fat.extend(cpusubtype.to_be_bytes());
+        fat.extend((index << align).to_be_bytes());
+        fat.extend(u32::try_from(archive.len()).unwrap().to_be_bytes());
+        fat.extend(align.to_be_bytes());
+    }
+    for index in 1..=2 {
+        fat.resize(index << align, 0);
+        fat.extend(&archive);
+    }
+
+    let binaries = checksec_binaries(&fat, Path::new("libfoo.a"));
+    assert_eq!(
+        binaries.iter().map(|binary| &binary.file).collect::<Vec<_>>(),
+        vec![
+            &PathBuf::from("libfoo.a\u{2794}x86_64\u{2794}rel_cl.o"),
+            &PathBuf::from("libfoo.a\u{2794}arm64\u{2794}rel_cl.o"),
+        ]
+    );
+    for (binary, architecture) in binaries.iter().zip(["x86_64", "arm64"]) {
+        assert_eq!(blob_types(binary), vec![BinType::MachO64]);
+        let slice = binary.blobs[0].slice.as_ref().expect("slice missing");
+        assert_eq!(slice.architecture, architecture);
+    }
+}
@@ -0,0 +1,105 @@
+use checksec::{
+    checksec, checksec_binaries,
+    policy::{Policy, PolicyError},
+};
+use serde_json::json;
+use std::path::Path;
+mod utils;
+use utils::file_to_buf;
+
+const ELF_POLICY: &str = r#"
+[elf]
+relro = "Full"
+pie = ["PIE", "DSO"]
+canary = true
+rpath = "None"
+"#;
+
+#[test]
+fn test_policy_satisfied() {
+    let policy = Policy::parse(ELF_POLICY).expect("policy parsing failed");
+    let filename = "./tests/binaries/elf/all".into();
+    let buf = file_to_buf(&filename);
+    assert!(policy.check(&[checksec(&buf, filename)]).is_empty());
+}
+
+#[test]
+fn test_policy_violated() {
+    let policy = Policy::parse(ELF_POLICY).expect("policy parsing failed");
+    let filename = "./tests/binaries/elf/none".into();
+    let buf = file_to_buf(&filename);
+    let violations = policy.check(&[checksec(&buf, filename)]);
+    let properties: Vec<&str> =
+        violations.iter().map(|v| v.property.as_str()).collect();
+    assert_eq!(properties, ["canary", "pie", "relro"]);
+    assert_eq!(violations[0].actual, Some(json!(false)));
+    assert_eq!(violations[1].expected, json!(["PIE", "DSO"]));
+}
+
+#[test]
+fn test_policy_rpath() {
+    let policy = Policy::parse(ELF_POLICY).expect("policy parsing failed");
+    let filename = "./tests/binaries/elf/rpath".into();
+    let buf = file_to_buf(&filename);
+    let violations = policy.check(&[checksec(&buf, filename)]);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].property, "rpath");
+    assert_eq!(violations[0].actual, Some(json!("./")));
+}
+
+#[test]
+fn test_policy_insecure_search_paths() {
+    let policy = Policy::parse("[elf]\ninsecure_search_paths = false")
+        .expect("policy parsing failed");
+    let filename = "./tests/binaries/elf/all".into();
+    let buf = file_to_buf(&filename);
+    assert!(policy.check(&[checksec(&buf, filename)]).is_empty());
+
+    // missing and relative search path directories after expansion
+    let sysroot = Path::new("./tests/binaries/ldso/sysroot");
+    let filename = "./tests/binaries/ldso/sysroot/usr/bin/app-dst".into();
+    let buf = file_to_buf(&filename);
+    let mut binary = checksec(&buf, filename);
+    binary.check_search_dirs(sysroot);
+    let violations = policy.check(&[binary]);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].property, "insecure_search_paths");
+    assert_eq!(violations[0].actual, Some(json!(true)));
+}
+
+#[test]
+fn test_policy_json_pe() {
+    let policy = Policy::parse(
+        r#"{"pe": {"aslr": "HighEntropyVa", "cfg": true, "unknown": 1}}"#,
+    )
+    .expect("policy parsing failed");
+    let filename =
+        "./tests/binaries/pe/debug_directories-clang_lld.exe.bin".into();
+    let buf = file_to_buf(&filename);
+    let violations = policy.check(&[checksec(&buf, filename)]);
+    let properties: Vec<(&str, bool)> = violations
+        .iter()
+        .map(|v| (v.property.as_str(), v.actual.is_some()))
+        .collect();
+    assert_eq!(properties, [("cfg", true), ("unknown", false)]);
+}
+
+#[test]
+fn test_policy_parse_error() {
+    let policy = Policy::parse(ELF_POLICY).expect("policy parsing failed");
+    let buf = file_to_buf(&"./tests/binaries/elf/all".into());
+    let binaries = checksec_binaries(&buf[..64], Path::new("bin/truncated"));
+    let violations = policy.check(&binaries);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].property, "error");
+    assert!(violations[0].to_string().starts_with("bin/truncated: "));
+}
+
+#[test]
+fn test_policy_invalid() {
+    assert!(matches!(
+        Policy::parse("[coff]\nnx = true"),
+        Err(PolicyError::Toml(_))
+    ));
+    assert!(matches!(Policy::parse("{"), Err(PolicyError::Json(_))));
+}
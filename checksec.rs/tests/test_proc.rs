@@ -0,0 +1,408 @@
+#![cfg(target_os = "linux")]
+use checksec::{
+    binary::BinSpecificProperties,
+    checksec,
+    proc::{
+        Capabilities, Context, GroupBy, GroupedExecutable, MappedFile, ProcFs,
+        Process, Processes, Runtime, Seccomp, StaleImage,
+    },
+    sarif::get_sarif_process_report,
+    severity::SeverityProfile,
+    shared::Rpath,
+};
+use serde_json::{json, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+mod utils;
+use utils::file_to_buf;
+
+const SNAPSHOT: &str = "./tests/binaries/proc/snapshot";
+
+#[test]
+fn test_runtime_status() {
+    let status = fs::read_to_string("./tests/binaries/proc/status")
+        .expect("status not read");
+    let runtime = Runtime::parse_status(&status);
+    assert_eq!(runtime.seccomp, Some(Seccomp::Filter));
+    assert_eq!(runtime.seccomp_filters, Some(12));
+    assert_eq!(runtime.no_new_privs, Some(true));
+    assert_eq!(
+        runtime.capabilities,
+        Some(Capabilities { effective: 0, permitted: 0, bounding: 0x3000 })
+    );
+    assert_eq!(
+        Capabilities::names(0x3000),
+        vec!["cap_net_admin", "cap_net_raw"]
+    );
+    // only known for running processes
+    assert_eq!(runtime.personality, None);
+
+    // kernels before seccomp support lack the fields
+    let runtime = Runtime::parse_status("Name:\tinit\nCapEff:\t0\n");
+    assert_eq!(runtime.seccomp, None);
+    assert_eq!(runtime.no_new_privs, None);
+    assert_eq!(runtime.capabilities, None);
+}
+
+#[test]
+fn test_runtime_personality() {
+    let runtime =
+        Runtime { personality: Some(0x0040_0000), ..Runtime::default() };
+    assert!(runtime.read_implies_exec());
+    assert!(!runtime.addr_no_randomize());
+    let runtime =
+        Runtime { personality: Some(0x0004_0000), ..Runtime::default() };
+    assert!(runtime.addr_no_randomize());
+}
+
+#[test]
+fn test_runtime_self() {
+    let exe = std::env::current_exe().expect("no executable");
+    let pid = std::process::id() as usize;
+    let procfs = ProcFs::default();
+    let runtime = procfs.runtime(pid).expect("runtime not read");
+    assert!(runtime.seccomp.is_some());
+    assert_eq!(runtime.personality.map(|flags| flags & 0x0040_0000), Some(0));
+    // the running image is the file at its path
+    let executable = procfs.executable(pid).expect("executable not read");
+    assert_eq!(executable.file, exe);
+    assert!(!executable.stale);
+}
+
+#[test]
+fn test_procfs_snapshot() {
+    let procfs = ProcFs::new(SNAPSHOT);
+    assert_eq!(procfs.pids().expect("pids not read"), vec![2, 4242, 4243]);
+    assert_eq!(procfs.name(4242).as_deref(), Some("app"));
+    assert_eq!(procfs.exe(4242), Some(PathBuf::from("/usr/bin/app")));
+    // kernel threads have no executable
+    assert_eq!(procfs.name(2).as_deref(), Some("kthreadd"));
+    assert_eq!(procfs.exe(2), None);
+
+    // files are read through the root of the process
+    let exe = procfs
+        .resolve(4242, Path::new("/usr/bin/app"))
+        .expect("file not resolved");
+    assert_eq!(exe, Path::new(SNAPSHOT).join("4242/root/usr/bin/app"));
+    // and never from the host
+    assert!(procfs.resolve(4242, Path::new("/bin/sh")).is_err());
+    assert!(procfs.resolve(4243, Path::new("/bin/sh")).is_err());
+    // and so are the search paths of its binaries
+    let sysroot = procfs.sysroot(4242).expect("root not read");
+    assert_eq!(sysroot, Path::new(SNAPSHOT).join("4242/root"));
+    assert_eq!(
+        ProcFs::default()
+            .sysroot(std::process::id() as usize)
+            .expect("root not read"),
+        Path::new("/")
+    );
+    let mut dst = checksec(
+        &file_to_buf(&"./tests/binaries/ldso/sysroot/usr/bin/app-dst".into()),
+        "/usr/bin/app-dst".into(),
+    );
+    dst.check_search_dirs(&sysroot);
+    match &dst.blobs[0].properties {
+        BinSpecificProperties::Elf(elf_result) => assert_eq!(
+            *elf_result.rpath,
+            [
+                Rpath::Yes("${ORIGIN}/../lib".into()),
+                Rpath::Missing("$ORIGIN/missing".into()),
+                Rpath::Relative("relative".into()),
+                Rpath::Missing("/$LIB".into()),
+                Rpath::Relative("$ORIGINAL".into()),
+            ]
+        ),
+        _ => panic!("Checksec failed"),
+    }
+    let binary = checksec(
+        &file_to_buf(&exe.to_string_lossy().to_string()),
+        "/usr/bin/app".into(),
+    );
+    let process = procfs.process(4242, binary, None);
+    let runtime = process.runtime.expect("runtime not read");
+    assert_eq!(runtime.seccomp, Some(Seccomp::Filter));
+    assert_eq!(runtime.personality, Some(0));
+}
+
+#[test]
+fn test_procfs_deleted_exe() {
+    let procfs = ProcFs::new(SNAPSHOT);
+    assert_eq!(procfs.exe(4243), Some(PathBuf::from("/usr/bin/old")));
+    assert_eq!(
+        procfs.executable(4243),
+        Some(MappedFile {
+            file: PathBuf::from("/usr/bin/old"),
+            source: Path::new(SNAPSHOT).join("4243/exe"),
+            stale: true,
+        })
+    );
+    let executable = procfs.executable(4242).expect("executable not read");
+    assert!(!executable.stale);
+    assert_eq!(
+        executable.source,
+        Path::new(SNAPSHOT).join("4242/root/usr/bin/app")
+    );
+}
+
+#[test]
+fn test_stale_image() {
+    let mapped = MappedFile {
+        file: PathBuf::from("/usr/bin/app"),
+        source: PathBuf::from("/proc/42/exe"),
+        stale: true,
+    };
+    let scan = |filename: &str| {
+        let filename = filename.to_string();
+        let mut binary = checksec(&file_to_buf(&filename), filename);
+        binary.file = mapped.file.clone();
+        binary
+    };
+    let running = scan("./tests/binaries/elf/none");
+    let replacement = scan("./tests/binaries/elf/all");
+
+    let image = StaleImage::new(&mapped, Some(&running), Some(&replacement));
+    assert_eq!(image.file, mapped.file);
+    let comparison = image.replacement.expect("comparison missing");
+    assert_eq!(comparison.left, mapped.source);
+    assert_eq!(comparison.right, mapped.file);
+    assert!(!comparison.is_equal());
+
+    let image = StaleImage::new(&mapped, Some(&running), Some(&running));
+    assert!(image.replacement.expect("comparison missing").is_equal());
+    // deleted without replacement
+    assert_eq!(
+        StaleImage::new(&mapped, Some(&running), None).replacement,
+        None
+    );
+
+    let processes = Processes::new(vec![Process {
+        pid: 42,
+        context: None,
+        binary: running.clone(),
+        runtime: None,
+        #[cfg(feature = "maps")]
+        maps: None,
+        #[cfg(feature = "maps")]
+        map_summary: None,
+        libraries: None,
+        stale: vec![StaleImage::new(
+            &mapped,
+            Some(&running),
+            Some(&replacement),
+        )],
+    }]);
+    let sarif: Value = serde_json::from_str(
+        &get_sarif_process_report(&processes, &SeverityProfile::default())
+            .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF");
+    let result = sarif["runs"][0]["results"]
+        .as_array()
+        .expect("results")
+        .iter()
+        .find(|result| result["ruleId"] == "stale_image")
+        .expect("stale image not reported");
+    assert_eq!(result["level"], json!("warning"));
+    assert!(result["message"]["text"].as_str().expect("message").starts_with(
+        "/usr/bin/app was replaced since it was loaded, changed:"
+    ));
+}
+
+#[test]
+fn test_procfs_deleted_self() {
+    // a copy of a binary which is deleted while it runs
+    let exe = std::env::temp_dir()
+        .join(format!("checksec-deleted-{}", std::process::id()));
+    fs::copy("/bin/sleep", &exe).expect("binary not copied");
+    let mut child =
+        std::process::Command::new(&exe).arg("10").spawn().expect("not run");
+    fs::remove_file(&exe).expect("binary not removed");
+
+    let pid = child.id() as usize;
+    let executable = ProcFs::default().executable(pid);
+    child.kill().expect("not killed");
+    child.wait().expect("not waited");
+
+    let executable = executable.expect("executable not read");
+    assert_eq!(executable.file, exe);
+    assert!(executable.stale);
+    assert_eq!(executable.source, PathBuf::from(format!("/proc/{pid}/exe")));
+}
+
+const CONTAINER: &str =
+    "3f4e5d6c7b8a99887766554433221100ffeeddccbbaa00112233445566778899";
+
+#[test]
+fn test_context_cgroup() {
+    // unified hierarchy
+    assert_eq!(
+        Context::parse_cgroup("0::/system.slice/nginx.service\n").as_deref(),
+        Some("/system.slice/nginx.service")
+    );
+    // the systemd hierarchy of cgroup v1
+    assert_eq!(
+        Context::parse_cgroup(
+            "12:pids:/user.slice\n1:name=systemd:/system.slice/cron.service\n"
+        )
+        .as_deref(),
+        Some("/system.slice/cron.service")
+    );
+    assert_eq!(Context::parse_cgroup(""), None);
+
+    for cgroup in [
+        format!("/system.slice/docker-{CONTAINER}.scope"),
+        format!("/docker/{CONTAINER}"),
+        format!(
+            "/kubepods.slice/kubepods-besteffort.slice/\
+            kubepods-besteffort-pod0c1d.slice/cri-containerd-{CONTAINER}.scope"
+        ),
+    ] {
+        assert_eq!(
+            Context::container_id(&cgroup).as_deref(),
+            Some(CONTAINER),
+            "{cgroup}"
+        );
+    }
+    assert_eq!(Context::container_id("/system.slice/nginx.service"), None);
+    assert_eq!(
+        Context::unit("/user.slice/user-1000.slice/session-2.scope")
+            .as_deref(),
+        Some("session-2.scope")
+    );
+    assert_eq!(Context::unit("/user.slice"), None);
+}
+
+#[test]
+fn test_procfs_context() {
+    let procfs = ProcFs::new(SNAPSHOT);
+    let context = procfs.context(4242).expect("context not read");
+    assert_eq!(
+        context,
+        Context {
+            ppid: Some(1),
+            uid: Some(991),
+            // from the passwd of the container
+            user: Some("systemd-resolve".into()),
+            cgroup: Some(format!("/system.slice/docker-{CONTAINER}.scope")),
+            container: Some(CONTAINER.into()),
+            unit: Some(format!("docker-{CONTAINER}.scope")),
+            namespace: Some(4_026_532_290),
+        }
+    );
+    let context = procfs.context(4243).expect("context not read");
+    assert_eq!(context.ppid, Some(4242));
+    assert_eq!(context.uid, Some(1000));
+    assert_eq!(context.container, None);
+    assert_eq!(context.unit.as_deref(), Some("old.service"));
+    assert_eq!(context.namespace, None);
+}
+
+#[test]
+fn test_process_groups() {
+    let process = |pid: usize, filename: &str, container: Option<&str>| {
+        let filename = filename.to_string();
+        Process {
+            pid,
+            binary: checksec(&file_to_buf(&filename), filename),
+            context: Some(Context {
+                container: container.map(str::to_string),
+                ..Context::default()
+            }),
+            runtime: None,
+            #[cfg(feature = "maps")]
+            maps: None,
+            #[cfg(feature = "maps")]
+            map_summary: None,
+            libraries: None,
+            stale: vec![],
+        }
+    };
+    let pids = |executable: &GroupedExecutable| {
+        executable
+            .processes
+            .iter()
+            .map(|process| process.pid)
+            .collect::<Vec<_>>()
+    };
+    let mut confined =
+        process(10, "./tests/binaries/elf/none", Some(CONTAINER));
+    confined.runtime = Some(Runtime::default());
+    let processes = Processes::new(vec![
+        process(30, "./tests/binaries/elf/all", None),
+        process(20, "./tests/binaries/elf/none", Some(CONTAINER)),
+        confined,
+        process(40, "./tests/binaries/elf/all", Some(CONTAINER)),
+    ]);
+
+    let groups = processes.grouped(GroupBy::Container);
+    assert_eq!(groups.groups.len(), 2);
+    let container = &groups.groups[0];
+    assert_eq!(container.key.as_deref(), Some(CONTAINER));
+    assert_eq!(container.len(), 3);
+    assert_eq!(container.executables.len(), 2);
+    assert_eq!(pids(&container.executables[0]), vec![10, 20]);
+    assert_eq!(pids(&container.executables[1]), vec![40]);
+    // the state of every process is kept
+    let executable = &container.executables[0];
+    assert_eq!(executable.processes[0].runtime, Some(Runtime::default()));
+    assert_eq!(executable.processes[1].runtime, None);
+    // processes outside of containers come last
+    assert_eq!(groups.groups[1].key, None);
+    assert_eq!(pids(&groups.groups[1].executables[0]), vec![30]);
+
+    let groups = processes.grouped(GroupBy::Executable);
+    assert_eq!(
+        groups
+            .groups
+            .iter()
+            .map(|group| group.key.as_deref())
+            .collect::<Vec<_>>(),
+        vec![
+            Some("./tests/binaries/elf/all"),
+            Some("./tests/binaries/elf/none")
+        ]
+    );
+    let executable = &groups.groups[0].executables[0];
+    assert_eq!(pids(executable), vec![30, 40]);
+    let containers = executable
+        .processes
+        .iter()
+        .map(|process| process.context.as_ref()?.container.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(containers, vec![None, Some(CONTAINER)]);
+    assert_eq!(
+        serde_json::to_value(&groups).expect("serialization failed")
+            ["group_by"],
+        json!("executable")
+    );
+
+    // processes of an executable with other libraries are kept apart
+    let library = |filename: &str| {
+        let filename = filename.to_string();
+        checksec(&file_to_buf(&filename), filename)
+    };
+    let mut loaded = process(50, "./tests/binaries/elf/all", None);
+    loaded.libraries = Some(vec![
+        library("./tests/binaries/elf/dso.so"),
+        library("./tests/binaries/elf/dso_cl.so"),
+    ]);
+    let mut reordered = process(60, "./tests/binaries/elf/all", None);
+    reordered.libraries = Some(vec![
+        library("./tests/binaries/elf/dso_cl.so"),
+        library("./tests/binaries/elf/dso.so"),
+    ]);
+    let processes = Processes::new(vec![
+        process(30, "./tests/binaries/elf/all", None),
+        loaded,
+        reordered,
+    ]);
+    let groups = processes.grouped(GroupBy::Executable);
+    let executables = &groups.groups[0].executables;
+    assert_eq!(executables.len(), 2);
+    assert_eq!(pids(&executables[0]), vec![30]);
+    assert!(executables[0].libraries.is_none());
+    assert_eq!(pids(&executables[1]), vec![50, 60]);
+    assert_eq!(executables[1].libraries.as_ref().map(Vec::len), Some(2));
+}
@@ -0,0 +1,427 @@
+use checksec::{
+    checksec,
+    sarif::{
+        get_sarif_baseline_report, get_sarif_comparison, get_sarif_report,
+        get_sarif_report_with_profile,
+    },
+    severity::SeverityProfile,
+};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+mod utils;
+use utils::file_to_buf;
+
+#[test]
+fn test_sarif_nested_libraries() {
+    let filename = "./tests/binaries/elf/all".into();
+    let mut binary = checksec(&file_to_buf(&filename), filename);
+    let libname = "./tests/binaries/elf/none".into();
+    binary.libraries = vec![checksec(&file_to_buf(&libname), libname)];
+
+    let report: Value = serde_json::from_str(
+        &get_sarif_report(&[binary]).expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF JSON");
+    let runs = report["runs"].as_array().expect("runs missing");
+    assert_eq!(runs.len(), 1);
+
+    let artifacts =
+        runs[0]["artifacts"].as_array().expect("artifacts missing");
+    assert_eq!(artifacts.len(), 2);
+    assert_eq!(
+        artifacts[0]["location"]["uri"],
+        json!("./tests/binaries/elf/all")
+    );
+    assert_eq!(artifacts[0].get("parentIndex"), None);
+    assert_eq!(
+        artifacts[0]["hashes"]["sha-256"],
+        json!(
+            "130143dfd348ceb43a0da38be1376d0a9a1f051c30c2cf398e09943415c682d0"
+        )
+    );
+    assert!(artifacts[0]["length"].as_u64().is_some_and(|length| length > 0));
+    assert_eq!(
+        artifacts[1]["location"]["uri"],
+        json!("./tests/binaries/elf/none")
+    );
+    assert_eq!(artifacts[1]["parentIndex"], json!(0));
+
+    let results = runs[0]["results"].as_array().expect("results missing");
+    let index_of = |idx: u64| {
+        results.iter().filter(move |result| {
+            result["locations"][0]["physicalLocation"]["artifactLocation"]
+                ["index"]
+                == json!(idx)
+        })
+    };
+    assert!(index_of(0).count() > 0);
+    assert_eq!(index_of(0).count(), index_of(1).count());
+    assert!(index_of(1).any(|result| {
+        result["message"]["text"] == json!("Stack Canary enabled: false")
+    }));
+}
+
+#[test]
+fn test_sarif_error_blobs() {
+    let filename = "./tests/binaries/elf/all".into();
+    let binary = checksec(&file_to_buf(&filename), filename);
+    let invalid = checksec(b"not a binary", "./notes.txt".into());
+
+    let report: Value = serde_json::from_str(
+        &get_sarif_report(&[binary, invalid])
+            .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF JSON");
+    let runs = report["runs"].as_array().expect("runs missing");
+    assert_eq!(runs.len(), 2);
+
+    let invocation = &runs[0]["invocations"][0];
+    assert_eq!(invocation["executionSuccessful"], json!(true));
+    assert_eq!(invocation.get("toolExecutionNotifications"), None);
+    assert!(!runs[0]["results"].as_array().expect("results").is_empty());
+
+    let invocation = &runs[1]["invocations"][0];
+    assert_eq!(invocation["executionSuccessful"], json!(false));
+    let notifications = invocation["toolExecutionNotifications"]
+        .as_array()
+        .expect("notifications missing");
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["level"], json!("error"));
+    assert_eq!(notifications[0]["descriptor"]["id"], json!("parse_error"));
+    assert_eq!(
+        notifications[0]["locations"][0]["physicalLocation"]
+            ["artifactLocation"],
+        json!({"index": 0, "uri": "./notes.txt"})
+    );
+    assert_eq!(
+        runs[1]["artifacts"][0]["location"]["uri"],
+        json!("./notes.txt")
+    );
+    assert_eq!(runs[1]["results"], json!([]));
+}
+
+#[test]
+fn test_sarif_error_library() {
+    let filename = "./tests/binaries/elf/all".into();
+    let mut binary = checksec(&file_to_buf(&filename), filename);
+    binary.libraries =
+        vec![checksec(b"not a binary", "./libbroken.so".into())];
+
+    let report: Value = serde_json::from_str(
+        &get_sarif_report(&[binary]).expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF JSON");
+    let run = &report["runs"][0];
+    assert_eq!(run["artifacts"][1]["parentIndex"], json!(0));
+    let invocation = &run["invocations"][0];
+    assert_eq!(invocation["executionSuccessful"], json!(false));
+    assert_eq!(
+        invocation["toolExecutionNotifications"][0]["locations"][0]
+            ["physicalLocation"]["artifactLocation"]["index"],
+        json!(1)
+    );
+    assert!(!run["results"].as_array().expect("results").is_empty());
+}
+
+#[test]
+fn test_sarif_rules() {
+    let mut reports = Vec::new();
+    for filename in [
+        "./tests/binaries/elf/all",
+        "./tests/binaries/pe/debug_directories-clang_lld.exe.bin",
+        "./tests/binaries/Mach-O/basic",
+    ] {
+        let filename = filename.to_string();
+        let buf = file_to_buf(&filename);
+        let report: Value = serde_json::from_str(
+            &get_sarif_report(&[checksec(&buf, filename)])
+                .expect("SARIF generation failed"),
+        )
+        .expect("invalid SARIF JSON");
+        reports.push(report);
+    }
+
+    for report in &reports {
+        let run = &report["runs"][0];
+        let driver = &run["tool"]["driver"];
+        assert_eq!(driver["version"], json!(checksec::VERSION));
+        let rules = driver["rules"].as_array().expect("rules missing");
+        for rule in rules {
+            assert!(rule["shortDescription"]["text"].is_string());
+            assert!(rule["fullDescription"]["text"].is_string());
+            assert!(rule["help"]["text"].is_string());
+            assert!(rule["defaultConfiguration"]["level"].is_string());
+        }
+
+        let results = run["results"].as_array().expect("results missing");
+        assert!(!results.is_empty());
+        for result in results {
+            let index = result["ruleIndex"].as_u64().expect("ruleIndex");
+            let index = usize::try_from(index).expect("ruleIndex");
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+            assert!(
+                result["partialFingerprints"]["binaryRuleHash/v1"].is_string()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_sarif_fingerprints() {
+    let fingerprints_at = |filename: &str, path: String| {
+        let buf = file_to_buf(&filename.to_string());
+        let report: Value = serde_json::from_str(
+            &get_sarif_report(&[checksec(&buf, path)])
+                .expect("SARIF generation failed"),
+        )
+        .expect("invalid SARIF JSON");
+        report["runs"][0]["results"]
+            .as_array()
+            .expect("results missing")
+            .iter()
+            .map(|result| {
+                result["partialFingerprints"]["binaryRuleHash/v1"].clone()
+            })
+            .collect::<Vec<Value>>()
+    };
+    let fingerprints =
+        |filename: &str| fingerprints_at(filename, filename.to_string());
+    let all = fingerprints("./tests/binaries/elf/all");
+    // stable across scans
+    assert_eq!(all, fingerprints("./tests/binaries/elf/all"));
+    // derived from the content of the file, not its path
+    assert_eq!(
+        all,
+        fingerprints_at("./tests/binaries/elf/all", "/opt/all".to_string())
+    );
+    // unique per rule and per binary
+    let unique: HashSet<&Value> = all.iter().collect();
+    assert_eq!(unique.len(), all.len());
+    let none = fingerprints("./tests/binaries/elf/none");
+    assert!(all.iter().all(|fingerprint| !none.contains(fingerprint)));
+}
+
+fn rule_levels(report: &str) -> HashMap<String, Value> {
+    let report: Value = serde_json::from_str(report).expect("invalid SARIF");
+    report["runs"][0]["results"]
+        .as_array()
+        .expect("results missing")
+        .iter()
+        .map(|result| {
+            (
+                result["ruleId"].as_str().expect("ruleId").to_string(),
+                result["level"].clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_sarif_severity_builtin() {
+    let filename = "./tests/binaries/elf/none".into();
+    let binaries = [checksec(&file_to_buf(&filename), filename)];
+    let levels = |profile: &str| {
+        let profile = SeverityProfile::builtin(profile).expect("profile");
+        rule_levels(
+            &get_sarif_report_with_profile(&binaries, &profile)
+                .expect("SARIF generation failed"),
+        )
+    };
+
+    let default = levels("default");
+    assert_eq!(
+        default,
+        rule_levels(&get_sarif_report(&binaries).expect("SARIF failed"))
+    );
+    assert_eq!(default["canary"], json!("warning"));
+    assert_eq!(levels("strict")["canary"], json!("error"));
+    assert_eq!(levels("informational")["canary"], json!("note"));
+    // informational results and passing checks keep their level
+    for profile in ["strict", "informational"] {
+        let levels = levels(profile);
+        assert_eq!(levels["architecture"], default["architecture"]);
+        assert_eq!(levels["ASan"], default["ASan"]);
+    }
+    assert!(SeverityProfile::builtin("unknown").is_none());
+}
+
+#[test]
+fn test_sarif_severity_mapping() {
+    let profile = SeverityProfile::parse(
+        r#"
+level = "error"
+
+[rules]
+canary = "note"
+"#,
+    )
+    .expect("profile parsing failed");
+    let filename = "./tests/binaries/elf/none".into();
+    let binaries = [checksec(&file_to_buf(&filename), filename)];
+    let report = get_sarif_report_with_profile(&binaries, &profile)
+        .expect("SARIF generation failed");
+    let levels = rule_levels(&report);
+    assert_eq!(levels["canary"], json!("note"));
+    assert_eq!(levels["relro"], json!("error"));
+
+    // the rules table reflects the profile
+    let report: Value = serde_json::from_str(&report).expect("invalid SARIF");
+    let rules = report["runs"][0]["tool"]["driver"]["rules"]
+        .as_array()
+        .expect("rules missing");
+    let level = |id: &str| {
+        rules
+            .iter()
+            .find(|rule| rule["id"] == json!(id))
+            .map(|rule| rule["defaultConfiguration"]["level"].clone())
+    };
+    assert_eq!(level("canary"), Some(json!("note")));
+    assert_eq!(level("relro"), Some(json!("error")));
+    assert_eq!(level("architecture"), Some(json!("note")));
+
+    assert!(
+        SeverityProfile::parse(r#"{"rules": {"canary": "high"}}"#).is_err()
+    );
+    // rule ids are checked, also against the kernel checks
+    assert!(
+        SeverityProfile::parse(r#"{"rules": {"safeSEH": "error"}}"#).is_err()
+    );
+    assert!(SeverityProfile::parse(
+        r#"{"rules": {"CONFIG_STACKPROTECTOR_STRONG": "error"}}"#
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_sarif_severity_raise_note() {
+    let filename = "./tests/binaries/elf/dso.so".into();
+    let binaries = [checksec(&file_to_buf(&filename), filename)];
+    let default = rule_levels(
+        &get_sarif_report(&binaries).expect("SARIF generation failed"),
+    );
+    assert_eq!(default["PIE"], json!("note"));
+    // a rule level also applies to failed checks which are notes
+    let profile = SeverityProfile::parse("[rules]\nPIE = \"error\"\n")
+        .expect("profile parsing failed");
+    let levels = rule_levels(
+        &get_sarif_report_with_profile(&binaries, &profile)
+            .expect("SARIF generation failed"),
+    );
+    assert_eq!(levels["PIE"], json!("error"));
+    assert_eq!(levels["architecture"], default["architecture"]);
+}
+
+#[test]
+fn test_sarif_safeseh_64bit() {
+    let filename =
+        "./tests/binaries/pe/debug_directories-clang_lld.exe.bin".to_string();
+    let buf = file_to_buf(&filename);
+    let levels = rule_levels(
+        &get_sarif_report(&[checksec(&buf, filename.clone())])
+            .expect("SARIF failed"),
+    );
+    assert_eq!(levels["bitness"], json!("note"));
+    assert_eq!(levels["safeseh"], json!("note"));
+
+    // SafeSEH does not apply to 64-bit images, unless a profile says so
+    let binaries = [checksec(&buf, filename.clone())];
+    let levels = |profile: &SeverityProfile| {
+        rule_levels(
+            &get_sarif_report_with_profile(&binaries, profile)
+                .expect("SARIF generation failed"),
+        )
+    };
+    let strict = SeverityProfile::builtin("strict").expect("profile");
+    assert_eq!(levels(&strict)["safeseh"], json!("note"));
+    let profile = SeverityProfile::parse("[rules]\nsafeseh = \"error\"\n")
+        .expect("profile parsing failed");
+    assert_eq!(levels(&profile)["safeseh"], json!("error"));
+}
+
+#[test]
+fn test_sarif_comparison() {
+    let filename = "./tests/binaries/elf/all".into();
+    let baseline = checksec(&file_to_buf(&filename), filename);
+    let filename = "./tests/binaries/elf/none".into();
+    let current = checksec(&file_to_buf(&filename), filename);
+
+    let report: Value = serde_json::from_str(
+        &get_sarif_comparison(
+            &baseline,
+            &current,
+            &SeverityProfile::default(),
+        )
+        .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF JSON");
+    let runs = report["runs"].as_array().expect("runs missing");
+    assert_eq!(runs.len(), 1);
+    let state = |rule: &str| {
+        runs[0]["results"]
+            .as_array()
+            .expect("results missing")
+            .iter()
+            .find(|result| result["ruleId"] == json!(rule))
+            .map(|result| result["baselineState"].clone())
+    };
+    assert_eq!(state("canary"), Some(json!("updated")));
+    assert_eq!(state("architecture"), Some(json!("unchanged")));
+
+    let report: Value = serde_json::from_str(
+        &get_sarif_comparison(
+            &checksec(b"not a binary", "./notes.txt".into()),
+            &current,
+            &SeverityProfile::default(),
+        )
+        .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF JSON");
+    assert!(report["runs"][0]["results"]
+        .as_array()
+        .expect("results missing")
+        .iter()
+        .all(|result| result["baselineState"] == json!("new")));
+}
+
+#[test]
+fn test_sarif_baseline_report() {
+    let scan = |path: &str, filename: &str| {
+        checksec(&file_to_buf(&path.to_string()), filename.to_string())
+    };
+    let baseline = [
+        scan("./tests/binaries/elf/all", "release-1.0/bin/app"),
+        scan("./tests/binaries/elf/dso.so", "release-1.0/bin/old"),
+    ];
+    let current = [
+        scan("./tests/binaries/elf/none", "release-1.1/bin/app"),
+        scan("./tests/binaries/elf/all", "release-1.1/bin/new"),
+    ];
+    let report: Value = serde_json::from_str(
+        &get_sarif_baseline_report(
+            &baseline,
+            &current,
+            &SeverityProfile::default(),
+        )
+        .expect("SARIF generation failed"),
+    )
+    .expect("invalid SARIF JSON");
+    let runs = report["runs"].as_array().expect("runs missing");
+    // the removed binary has a run of its own
+    assert_eq!(runs.len(), 3);
+    let state = |run: usize, rule: &str| {
+        runs[run]["results"]
+            .as_array()
+            .expect("results missing")
+            .iter()
+            .find(|result| result["ruleId"] == json!(rule))
+            .map(|result| result["baselineState"].clone())
+    };
+    assert_eq!(state(0, "canary"), Some(json!("updated")));
+    assert_eq!(state(0, "architecture"), Some(json!("unchanged")));
+    assert_eq!(state(1, "canary"), Some(json!("new")));
+    assert_eq!(
+        runs[2]["artifacts"][0]["location"]["uri"],
+        json!("release-1.0/bin/old")
+    );
+    assert_eq!(state(2, "canary"), Some(json!("absent")));
+}
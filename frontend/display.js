@@ -164,7 +164,7 @@ export function displayBinaryData(binaryData, container) {
     container.appendChild(securityList);
 }
 
-export function displayShareFunctionality(blob, filename, version, container) {
+export function displayShareFunctionality(report, filename, version, container) {
     // Generate unique IDs for this tab's buttons
     const uniqueId = Date.now() + Math.random().toString(36).substr(2, 9);
     const sarifBtnId = `downloadSarifBtn_${uniqueId}`;
@@ -192,7 +192,7 @@ export function displayShareFunctionality(blob, filename, version, container) {
         downloadSarifBtn.textContent = 'Generating...';
         downloadSarifBtn.disabled = true;
         try {
-            const sarifJson = await generate_sarif_report([{file: filename, blobs: [blob], libraries: []}]);
+            const sarifJson = await generate_sarif_report([report]);
             const urlblob = new Blob([sarifJson], { type: 'application/json' });
             const url = URL.createObjectURL(urlblob);
             const a = document.createElement('a');
@@ -240,7 +240,7 @@ export function displayShareFunctionality(blob, filename, version, container) {
         shareBtn.textContent = 'Generating...';
         shareBtn.disabled = true;
         try {
-            const shareableUrl = await generateShareableURL({version, report: {file: filename, blobs: [blob], libraries: []}});
+            const shareableUrl = await generateShareableURL({version, report});
             shareUrlDiv.textContent = shareableUrl;
             shareUrlDiv.style.display = 'block';
             shareBtn.style.display = 'none';
@@ -424,7 +424,7 @@ function scrollToActiveTab() {
     }
 }
 
-export function displayResultV1(filename, blob, container) {
+export function displayResultV1(filename, blob, container, report) {
     const VERSION = '0.1.0';
     container.innerHTML = "";
     const [bt, binaryData] = Object.entries(blob.properties)[0];
@@ -432,7 +432,7 @@ export function displayResultV1(filename, blob, container) {
     displayFileHeader(blob.binarytype, filename, container);
     displayFileRow(filename, container);
     displayBinaryData(binaryData, container);
-    displayShareFunctionality(blob, filename, VERSION, container);
+    displayShareFunctionality(report, filename, VERSION, container);
 }
 
 export function displayResult(entry) {
@@ -452,12 +452,12 @@ export function displayResult(entry) {
     entry.report.blobs.forEach((blob, index) => {
         let container = setupResultTab(filename);
         if (blob.binarytype == "Error"){
-            display_error_handler(filename, blob.properties.Error, container);
+            display_error_handler(filename, blob.properties.Error.message, container);
         }
         else{
             const tabIndex = container.dataset.tabIndex;
-            tabResults.set(tabIndex, {filename, blob});
-            display_result_handler(filename, blob, container);
+            tabResults.set(tabIndex, {filename, blob, report: entry.report});
+            display_result_handler(filename, blob, container, entry.report);
         }
     });
 }
@@ -521,12 +521,7 @@ function addHeaderButtons() {
     }
     
     // Count successful results only
-    const successfulResults = [];
-    tabResults.forEach((entry, tabIndex) => {
-        if (!(entry.blob.binarytype === "Error")) {
-            successfulResults.push({file: entry.filename, blobs: [entry.blob], libraries: []});
-        }
-    });
+    const successfulResults = getSuccessfulReports();
     
     // Create button container for horizontal layout
     const buttonContainer = document.createElement("div");
@@ -606,13 +601,20 @@ function addHeaderButtons() {
     tabsContainer.insertBefore(buttonContainer, tabsHeader);
 }
 
-function updateCombinedButtonCount(button) {
-    const successfulResults = [];
+// Reports with at least one successfully analyzed blob, every blob of a
+// file has its own tab but shares the report of the file
+function getSuccessfulReports() {
+    const reports = new Set();
     tabResults.forEach((entry, tabIndex) => {
         if (!(entry.blob.binarytype === "Error")) {
-            successfulResults.push({file: entry.filename, blobs: [entry.blob], libraries: []});
+            reports.add(entry.report);
         }
     });
+    return [...reports];
+}
+
+function updateCombinedButtonCount(button) {
+    const successfulResults = getSuccessfulReports();
     
     button.innerHTML = `<span>Download Combined SARIF Report (${successfulResults.length} files)</span>`;
     button.disabled = false;
@@ -57,7 +57,7 @@ export async function handleFileInput(files) {
             const buffer = await file.arrayBuffer();
             const uint8Array = new Uint8Array(buffer);
             try{
-                results.push(await checksec_web(uint8Array, file.name));
+                results.push(...await checksec_web(uint8Array, file.name));
             }
             catch (err) {
                 console.log("Error message: ", err);
@@ -27,7 +27,7 @@ export function getNxClass(value) {
 }
 
 export function getPieClass(value) {
-    if (value === 'PIE') return 'secure';
+    if (value === 'PIE' || value === 'Module') return 'secure';
     if (value === 'DSO' || value === 'REL') return 'partial';
     if (value === 'None') return 'insecure';
     return 'info';
@@ -53,8 +53,12 @@ export function getPathClass(value) {
     return value.paths.length > 0 ? 'insecure' : 'secure';
 }
 
+export function getRwxSegmentsClass(value) {
+    return value.length > 0 ? 'insecure' : 'secure';
+}
+
 export function getBooleanClass(key, value) {
-    if (key === 'asan'){
+    if (key === 'asan' || key === 'textrel'){
         return value ? 'insecure': 'secure';
     }
     if (key == 'dyn_linking'){
@@ -78,7 +82,8 @@ export function getSecurityClass(key, value) {
         'pie': getPieClass,
         'fortify': getFortifyClass,
         'rpath': getPathClass,
-        'runpath': getPathClass
+        'runpath': getPathClass,
+        'rwx_segments': getRwxSegmentsClass
     };
 
     if (securityHandlers[key]) {
@@ -127,10 +132,16 @@ export function formatSecurityName(key) {
         'clang_cfi': 'Clang CFI',
         'clang_safestack': 'SafeStack',
         'stack_clash_protection': 'Stack Clash Protection',
+        'ibt': 'CET Indirect Branch Tracking',
+        'shstk': 'CET Shadow Stack',
+        'bti': 'Branch Target Identification',
+        'pac': 'Pointer Authentication',
         'fortify': 'Fortification',
         'fortified': 'Fortified Functions',
         'fortifiable': 'Fortifiable Functions',
         'nx': 'NX Bit',
+        'rwx_segments': 'RWX Segments',
+        'textrel': 'Text Relocations',
         'pie': 'Position Independent Executable',
         'relro': 'RELRO',
         'rpath': 'RPATH',
@@ -1,5 +1,5 @@
 import { checksec_web } from './pkg/checksec.js';
-import { displayResult } from './display.js';
+import { displayResults } from './display.js';
 import { showError, hideError } from './utils.js';
 import { getIsViewingSharedReport, setIsViewingSharedReport } from './share.js';
 
@@ -57,10 +57,10 @@ async function handleUrlAnalysis() {
         const filename = url.split('/').pop() || 'downloaded_file';
         
         // Run checksec analysis
-        const result = await checksec(uint8Array, filename);
+        const results = await checksec_web(uint8Array, filename);
         
         loading.style.display = 'none';
-        displayResult(result);
+        displayResults(results);
     } catch (err) {
         console.error("URL analysis error:", err);
         showError(err.message || "Failed to analyze file from URL");